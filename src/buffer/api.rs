@@ -1,8 +1,20 @@
+use std::io::Write;
+
+use crossterm::event::KeyEvent;
+
 use crate::Mode;
+use crate::buffer::clipboard::{ClipboardOption, ClipboardProvider};
+use crate::buffer::filter::{FilterError, FilterProvider};
 use crate::buffer::history::History;
 use crate::buffer::last_action::LastAction;
+use crate::buffer::mappings::{MappingFn, Mappings};
+use crate::buffer::marks::Marks;
 use crate::buffer::mode::BufferMode;
-use crate::buffer::registers::Registers;
+use crate::buffer::options::Options;
+use crate::buffer::position_list::PositionList;
+use crate::buffer::register_store::{RegisterHandle, SharedRegisters};
+use crate::buffer::registers::{RegisterEntry, RegisterType};
+use crate::event_parser::EventParsingError;
 use crate::utils::bounded_usize::BoundedUsize;
 
 /// Buffer that supports vim keymaps
@@ -29,20 +41,68 @@ use crate::utils::bounded_usize::BoundedUsize;
 /// ```
 #[derive(Debug, Default)]
 pub struct Buffer {
+    /// Positions of the latest changes, walked with `g;` and `g,`
+    pub(super) changes: PositionList,
     /// Content of the buffer
     pub(super) content: String,
     /// Position of the cursor within the buffer
     pub(super) cursor: BoundedUsize,
+    /// Region marked by a first `cx`, waiting to be exchanged with the next
+    /// one
+    pub(super) exchange: Option<(usize, usize)>,
+    /// Expression being typed for the `=` register, after `"=` or `<C-r>=`
+    pub(super) expression: Option<String>,
+    /// Filter used by `!`, installed by the host
+    pub(super) filter: Option<Box<dyn FilterProvider>>,
+    /// Command being typed for a filter, after `!{motion}`
+    pub(super) filter_command: Option<String>,
+    /// Error of the last filter that failed, until the host takes it
+    pub(super) filter_error: Option<FilterError>,
     /// Buffer history to restore old versions
     pub(super) history: History<Box<str>>,
+    /// Number of times the text typed in insert or replace mode is repeated
+    /// when leaving it, after a count like `3i`
+    pub(super) insert_count: usize,
+    /// Whether the text typed in insert mode is repeated on new lines, after
+    /// `{count}o`
+    pub(super) insert_new_lines: bool,
+    /// Position where insert mode was entered, kept before the text typed
+    /// since then, to know if `<BS>` deletes a char typed in this session
+    pub(super) insert_start: usize,
+    /// Text typed since entering insert mode, stored in the `.` register when
+    /// leaving it
+    pub(super) inserted: String,
+    /// Positions before the latest jumps, walked with `<C-o>` and `<C-i>`
+    pub(super) jumps: PositionList,
     /// Last performed action
     pub(super) last_action: LastAction,
+    /// Last command typed for a filter, run again by `.`
+    pub(super) last_filter: String,
+    /// Last tag typed for the surround commands, like `em` for `<em>`
+    pub(super) last_tag: String,
+    /// Operators, motions and text objects registered by the host
+    pub(super) mappings: Mappings,
+    /// Marks set automatically, like `'[` and `'^`
+    pub(super) marks: Marks,
     /// Vim mode of the buffer
     pub(super) mode: BufferMode,
+    /// Vim options, like `shiftwidth`
+    pub(super) options: Options,
+    /// Keys typed that start a mapping, waiting for the next keys
+    pub(super) pending_keys: Vec<KeyEvent>,
     /// Removed charracters when writing in replace mode
     pub(super) pre_replace_content: Vec<Option<char>>,
     /// Content of clipboard, from yanks and deletions, used by paste.
-    pub(super) registers: Registers,
+    ///
+    /// They can be shared with other buffers.
+    pub(super) registers: RegisterHandle,
+    /// Tag being typed for the surround commands, after `ys{motion}<` or
+    /// `cs{char}<`
+    pub(super) tag: Option<String>,
+    /// Number of nested transactions in progress
+    ///
+    /// History is only saved when no transaction is in progress.
+    pub(super) transaction_depth: usize,
 }
 
 impl Buffer {
@@ -80,6 +140,110 @@ impl Buffer {
         self.cursor.as_value()
     }
 
+    /// Returns the region marked by a first `cx{motion}`, that is waiting to
+    /// be exchanged with the next one, as the start and end byte indices.
+    ///
+    /// Hosts can use it to highlight the region. It is forgotten with `cxc`,
+    /// or when the buffer changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc def");
+    /// buffer.update_from_string("cxiw").unwrap();
+    /// assert_eq!(buffer.as_exchange(), Some((0, 3)));
+    ///
+    /// buffer.update_from_string("wcxiw").unwrap();
+    /// assert_eq!(buffer.as_content(), "def abc");
+    /// assert_eq!(buffer.as_exchange(), None);
+    /// ```
+    #[must_use]
+    pub const fn as_exchange(&self) -> Option<(usize, usize)> {
+        self.exchange
+    }
+
+    /// Returns the expression being typed for the `=` register, if any.
+    ///
+    /// An expression is typed after `"=` in normal mode or `<C-r>=` in insert
+    /// mode, and evaluated with `<CR>`. Hosts can display it like vim's
+    /// command line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("total: ");
+    /// buffer.update_from_string("$\"=6*7").unwrap();
+    /// assert_eq!(buffer.as_expression(), Some("6*7"));
+    /// buffer.update_from_string("<CR>p").unwrap();
+    /// assert_eq!(buffer.as_expression(), None);
+    /// assert_eq!(buffer.as_content(), "total: 42");
+    /// ```
+    #[must_use]
+    pub fn as_expression(&self) -> Option<&str> {
+        self.expression.as_deref()
+    }
+
+    /// Returns the command being typed for a filter, if any.
+    ///
+    /// The command is typed after `!{motion}` or `!!`, and run with `<CR>`.
+    /// Hosts can display it like vim's command line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.set_filter(|_: &str, input: &str| Ok(input.to_uppercase()));
+    /// buffer.update_from_string("!!upper").unwrap();
+    /// assert_eq!(buffer.as_filter_command(), Some("upper"));
+    /// buffer.update_from_string("<CR>").unwrap();
+    /// assert_eq!(buffer.as_filter_command(), None);
+    /// assert_eq!(buffer.as_content(), "ABC");
+    /// ```
+    #[must_use]
+    pub fn as_filter_command(&self) -> Option<&str> {
+        self.filter_command.as_deref()
+    }
+
+    /// Returns the position of an automatic mark, if it is set.
+    ///
+    /// The supported marks are:
+    /// - `'['` and `']'`: the first and last char of the last changed or yanked
+    ///   text,
+    /// - `'.'`: where the last change was made,
+    /// - `'^'`: where insert mode was last left, used by `gi`.
+    ///
+    /// Hosts can use them to highlight what just changed. The buffer has no
+    /// visual mode, so `'<'` and `'>'` are never set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc def");
+    /// assert_eq!(buffer.as_mark('['), None);
+    ///
+    /// buffer.update_from_string("wiXY<Esc>").unwrap();
+    /// assert_eq!(buffer.as_content(), "abc XYdef");
+    /// assert_eq!(buffer.as_mark('['), Some(4));
+    /// assert_eq!(buffer.as_mark(']'), Some(5));
+    /// assert_eq!(buffer.as_mark('.'), Some(5));
+    /// assert_eq!(buffer.as_mark('^'), Some(6));
+    /// ```
+    #[must_use]
+    pub fn as_mark(&self, name: char) -> Option<usize> {
+        match name {
+            '.' => self.changes.last(),
+            _ => self.marks.get(name),
+        }
+    }
+
     /// Returns the vim mode of the buffer (insert, normal, etc.)
     ///
     /// ```
@@ -97,6 +261,62 @@ impl Buffer {
         self.mode.to_mode()
     }
 
+    /// Returns the content of a register, with its [`RegisterType`].
+    ///
+    /// Returns `None` if the register is empty or doesn't exist. The content
+    /// is copied, as the registers may be shared with other buffers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, RegisterType};
+    ///
+    /// let mut buffer = Buffer::from("abc def");
+    /// buffer.update_from_string("\"ayw\"byy").unwrap();
+    /// assert_eq!(
+    ///     buffer.as_register('a'),
+    ///     Some(("abc ".to_owned(), RegisterType::Charwise))
+    /// );
+    /// assert_eq!(
+    ///     buffer.as_register('b'),
+    ///     Some(("abc def".to_owned(), RegisterType::Linewise))
+    /// );
+    /// assert_eq!(buffer.as_register('c'), None);
+    /// ```
+    #[must_use]
+    pub fn as_register(&mut self, reg: char) -> Option<(String, RegisterType)> {
+        self.registers
+            .lock()
+            .get(Some(reg))
+            .map(|(content, ty)| (content.to_owned(), ty))
+    }
+
+    /// Empties a register.
+    ///
+    /// This follows the same rules as [`Self::set_register`]: uppercase
+    /// registers empty the corresponding lowercase register, and `_` does
+    /// nothing. Emptying `+` or `*` also empties the clipboard, through the
+    /// clipboard provider and OSC 52, if enabled.
+    ///
+    /// Returns `false` if the register doesn't exist or is read-only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("secret");
+    /// buffer.update_from_string("yy").unwrap();
+    /// assert!(buffer.clear_register('"'));
+    /// assert!(buffer.clear_register('0'));
+    /// assert_eq!(buffer.as_register('"'), None);
+    ///
+    /// assert!(!buffer.clear_register('%'));
+    /// ```
+    pub fn clear_register(&mut self, reg: char) -> bool {
+        self.registers.lock().clear(reg)
+    }
+
     /// Returns `true` if the buffer is empty, and `false` otherwise.
     ///
     /// # Examples
@@ -126,6 +346,485 @@ impl Buffer {
     pub const fn len(&self) -> usize {
         self.content.len()
     }
+
+    /// Returns the name, content and type of every register that isn't
+    /// empty, like vim's `:registers`.
+    ///
+    /// Registers are listed in the same order as vim: the unnamed register,
+    /// the numbered registers, the named registers, then the special ones.
+    /// The content is copied, as the registers may be shared with other
+    /// buffers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, RegisterType};
+    ///
+    /// let mut buffer = Buffer::from("abc def");
+    /// buffer.update_from_string("\"byiwdw").unwrap();
+    /// assert_eq!(buffer.list_registers(), vec![
+    ///     ('"', "abc ".to_owned(), RegisterType::Charwise),
+    ///     ('b', "abc".to_owned(), RegisterType::Charwise),
+    ///     ('-', "abc ".to_owned(), RegisterType::Charwise),
+    /// ]);
+    /// ```
+    pub fn list_registers(&mut self) -> Vec<RegisterEntry> {
+        self.registers.lock().list()
+    }
+
+    /// Maps `keys` to a custom motion, that can be used alone or after an
+    /// operator, like `w`.
+    ///
+    /// The motion receives the content and the cursor, and returns the new
+    /// cursor, or `None` if it failed. It is repeated by counts, like `3w`.
+    /// Like most vim motions, it is exclusive: the char under the new cursor
+    /// isn't part of the scope of an operator.
+    ///
+    /// Mapped closures must be [`Send`], as the buffer holding them can be
+    /// moved to another thread.
+    ///
+    /// Keys that start a mapping are held until the mapping is fully typed.
+    /// If another key is typed instead, they get their usual meaning. Mapping
+    /// keys that are already mapped replaces the previous mapping.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `keys` isn't a valid keymap string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("a1 b2 c3");
+    /// buffer
+    ///     .map_motion("]d", |content: &str, cursor: usize| {
+    ///         content
+    ///             .char_indices()
+    ///             .find(|&(idx, ch)| idx > cursor && ch.is_ascii_digit())
+    ///             .map(|(idx, _)| idx)
+    ///     })
+    ///     .unwrap();
+    /// buffer.update_from_string("2]d").unwrap();
+    /// assert_eq!(buffer.as_cursor(), 4);
+    /// buffer.update_from_string("d]d").unwrap();
+    /// assert_eq!(buffer.as_content(), "a1 b3");
+    /// ```
+    pub fn map_motion<F>(
+        &mut self,
+        keys: &str,
+        motion: F,
+    ) -> Result<(), EventParsingError>
+    where
+        F: FnMut(&str, usize) -> Option<usize> + Send + 'static,
+    {
+        self.mappings.insert(keys, MappingFn::Motion(Box::new(motion)))
+    }
+
+    /// Maps `keys` to a custom operator, that is followed by a motion or a
+    /// text object, like `d`.
+    ///
+    /// The operator receives the text of the motion, and returns its
+    /// replacement, or `None` to leave the buffer unchanged. Typing the last
+    /// key of the mapping again applies it on the current line, like `gss`,
+    /// and counts apply to the motion. It is repeated with `.` and undone in
+    /// one step. As with [`Self::map_motion`], the closure must be [`Send`].
+    ///
+    /// See [`Self::map_motion`] for how keys are matched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `keys` isn't a valid keymap string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("c b a");
+    /// buffer
+    ///     .map_operator("gs", |text: &str| {
+    ///         let mut words = text.split(' ').collect::<Vec<_>>();
+    ///         words.sort_unstable();
+    ///         Some(words.join(" "))
+    ///     })
+    ///     .unwrap();
+    /// buffer.update_from_string("gss").unwrap();
+    /// assert_eq!(buffer.as_content(), "a b c");
+    /// buffer.update_from_string("uwgs$").unwrap();
+    /// assert_eq!(buffer.as_content(), "c a b");
+    /// ```
+    pub fn map_operator<F>(
+        &mut self,
+        keys: &str,
+        operator: F,
+    ) -> Result<(), EventParsingError>
+    where
+        F: FnMut(&str) -> Option<String> + Send + 'static,
+    {
+        self.mappings.insert(keys, MappingFn::Operator(Box::new(operator)))
+    }
+
+    /// Maps `keys` to a custom text object, that can be used after an
+    /// operator, like `iw`.
+    ///
+    /// The text object receives the content and the cursor, and returns the
+    /// start and the end of the object, the end being excluded, or `None` if
+    /// there is no object at the cursor.
+    ///
+    /// See [`Self::map_motion`] for how keys are matched, and why the closure
+    /// is [`Send`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `keys` isn't a valid keymap string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("see https://a.b/c now");
+    /// buffer
+    ///     .map_text_object("iu", |content: &str, cursor: usize| {
+    ///         let start = content.get(..cursor)?.rfind(' ')? + 1;
+    ///         let end = cursor + content.get(cursor..)?.find(' ')?;
+    ///         content.get(start..end)?.contains("://").then_some((start, end))
+    ///     })
+    ///     .unwrap();
+    /// buffer.update_from_string("fa\"uyiu").unwrap();
+    /// assert_eq!(buffer.as_register('u').unwrap().0, "https://a.b/c");
+    /// buffer.update_from_string("ciulink").unwrap();
+    /// assert_eq!(buffer.as_content(), "see link now");
+    /// ```
+    pub fn map_text_object<F>(
+        &mut self,
+        keys: &str,
+        object: F,
+    ) -> Result<(), EventParsingError>
+    where
+        F: FnMut(&str, usize) -> Option<(usize, usize)> + Send + 'static,
+    {
+        self.mappings.insert(keys, MappingFn::Object(Box::new(object)))
+    }
+
+    /// Sets the name of the alternate file, held by the read-only `#`
+    /// register.
+    ///
+    /// The buffer has no notion of files: this can be anything that makes
+    /// sense for the host, like the name of the previous field of a form.
+    pub fn set_alternate_file_name(&mut self, name: &str) {
+        self.registers.lock().set_read_only('#', name);
+    }
+
+    /// Starts new lines with the indent of the current one, like vim's
+    /// `autoindent` option.
+    ///
+    /// It applies to `o`, `O` and `<CR>` in insert mode, and is disabled by
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("fn main() {");
+    /// buffer.set_autoindent(true);
+    /// buffer.update_from_string("A<CR>    let a = 1;<CR>a += 1;").unwrap();
+    /// assert_eq!(buffer.as_content(), "fn main() {\n    let a = 1;\n    a += 1;");
+    /// ```
+    pub const fn set_autoindent(&mut self, autoindent: bool) {
+        self.options.autoindent = autoindent;
+    }
+
+    /// Sets the behaviour of the unnamed register regarding the clipboard, like
+    /// vim's `clipboard` option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, ClipboardOption, MemoryProvider};
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.set_clipboard_provider(MemoryProvider::default());
+    /// buffer.set_clipboard_option(ClipboardOption::UnnamedPlus);
+    /// buffer.update_from_string("yiw").unwrap();
+    /// assert_eq!(buffer.as_register('+').unwrap().0, "abc");
+    /// ```
+    pub fn set_clipboard_option(&mut self, option: ClipboardOption) {
+        self.registers.lock().set_clipboard_option(option);
+    }
+
+    /// Installs the provider used to read and write the clipboard registers
+    /// `+` and `*`.
+    ///
+    /// Without a provider, these registers behave like named registers.
+    pub fn set_clipboard_provider<P: ClipboardProvider + 'static>(
+        &mut self,
+        provider: P,
+    ) {
+        self.registers.lock().set_provider(Box::new(provider));
+    }
+
+    /// Sets the template of a commented line used by `gc`, like vim's
+    /// `commentstring` option.
+    ///
+    /// `%s` is replaced by the content of the line, like in `# %s`, `// %s`
+    /// or `<!-- %s -->`. It is `/* %s */` by default. Without `%s`, `gc`
+    /// does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("a = 1\n  b = 2");
+    /// buffer.set_commentstring("# %s");
+    /// buffer.update_from_string("gcip").unwrap();
+    /// assert_eq!(buffer.as_content(), "# a = 1\n#   b = 2");
+    /// buffer.update_from_string("gcip").unwrap();
+    /// assert_eq!(buffer.as_content(), "a = 1\n  b = 2");
+    /// ```
+    pub fn set_commentstring(&mut self, commentstring: &str) {
+        commentstring.clone_into(&mut self.options.commentstring);
+    }
+
+    /// Indents with spaces instead of tabs, like vim's `expandtab` option.
+    ///
+    /// It is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.update_from_string("<gt><gt>").unwrap();
+    /// assert_eq!(buffer.as_content(), "\tabc");
+    ///
+    /// buffer.set_expandtab(true);
+    /// buffer.set_shiftwidth(2);
+    /// buffer.update_from_string("<gt><gt>").unwrap();
+    /// assert_eq!(buffer.as_content(), "          abc");
+    /// ```
+    pub const fn set_expandtab(&mut self, expandtab: bool) {
+        self.options.expandtab = expandtab;
+    }
+
+    /// Sets the name of the current file, held by the read-only `%`
+    /// register.
+    ///
+    /// The buffer has no notion of files: this can be anything that makes
+    /// sense for the host, like the name of the current field of a form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, RegisterType};
+    ///
+    /// let mut buffer = Buffer::from("name: ");
+    /// buffer.set_file_name("email");
+    /// buffer.set_alternate_file_name("password");
+    /// buffer.update_from_string("$\"%p").unwrap();
+    /// assert_eq!(buffer.as_content(), "name: email");
+    ///
+    /// assert!(!buffer.set_register('%', "abc", RegisterType::Charwise));
+    /// assert_eq!(buffer.as_register('#').unwrap().0, "password");
+    /// ```
+    pub fn set_file_name(&mut self, name: &str) {
+        self.registers.lock().set_read_only('%', name);
+    }
+
+    /// Installs the filter used by `!{motion}` and `!!`.
+    ///
+    /// Without a filter, filtering fails with [`FilterError::NoFilter`]. See
+    /// [`FilterProvider`] for an example with a closure, and
+    /// [`crate::CommandFilter`] to run the commands in a shell.
+    pub fn set_filter<F: FilterProvider + 'static>(&mut self, filter: F) {
+        self.filter = Some(Box::new(filter));
+    }
+
+    /// Sets the flags that control automatic formatting, like vim's
+    /// `formatoptions` option.
+    ///
+    /// Only `t`, to wrap text while typing, and `c`, to wrap comments while
+    /// typing and continue their leader, are supported. They are both set by
+    /// default, but only apply once `textwidth` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::default();
+    /// buffer.set_textwidth(10);
+    /// buffer.update_from_string("ione two three").unwrap();
+    /// assert_eq!(buffer.as_content(), "one two\nthree");
+    ///
+    /// let mut buffer = Buffer::default();
+    /// buffer.set_textwidth(10);
+    /// buffer.set_formatoptions("");
+    /// buffer.update_from_string("ione two three").unwrap();
+    /// assert_eq!(buffer.as_content(), "one two three");
+    /// ```
+    pub fn set_formatoptions(&mut self, formatoptions: &str) {
+        formatoptions.clone_into(&mut self.options.formatoptions);
+    }
+
+    /// Sets the last command line, held by the read-only `:` register.
+    ///
+    /// The buffer has no command line: hosts that provide one can record the
+    /// last executed command here.
+    pub fn set_last_command(&mut self, command: &str) {
+        self.registers.lock().set_read_only(':', command);
+    }
+
+    /// Enables OSC 52 output for the clipboard registers `+` and `*`.
+    ///
+    /// When one of these registers is set, an OSC 52 escape sequence is
+    /// written to `writer`, usually the terminal, so that the terminal
+    /// emulator updates its clipboard. This works over SSH, where no
+    /// clipboard tool is available. Content longer than `max_len` bytes once
+    /// encoded in base64 is not sent, as many terminals reject it, but the
+    /// register is still set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.set_osc52_writer(Vec::new(), 100_000);
+    /// buffer.update_from_string("\"+yiw").unwrap();
+    /// assert_eq!(buffer.as_register('+').unwrap().0, "abc");
+    /// ```
+    pub fn set_osc52_writer<W: Write + Send + 'static>(
+        &mut self,
+        writer: W,
+        max_len: usize,
+    ) {
+        self.registers.lock().set_osc52(Box::new(writer), max_len);
+    }
+
+    /// Sets the content of a register, with an explicit [`RegisterType`].
+    ///
+    /// Uppercase registers append to the corresponding lowercase register,
+    /// and `_` discards the content.
+    ///
+    /// Returns `false` if the register doesn't exist or is read-only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, RegisterType};
+    ///
+    /// let mut buffer = Buffer::from("abc\ndef");
+    /// assert!(buffer.set_register('a', "12\n34", RegisterType::Blockwise));
+    /// buffer.update_from_string("\"aP").unwrap();
+    /// assert_eq!(buffer.as_content(), "12abc\n34def");
+    ///
+    /// assert!(!buffer.set_register('%', "abc", RegisterType::Charwise));
+    /// ```
+    pub fn set_register(
+        &mut self,
+        reg: char,
+        content: &str,
+        ty: RegisterType,
+    ) -> bool {
+        self.registers.lock().set(reg, content, ty)
+    }
+
+    /// Sets the number of columns of an indent level, used by `>>`, `<<`,
+    /// `<C-t>` and `<C-d>`, like vim's `shiftwidth` option.
+    ///
+    /// It is 8 by default, and `0` means the value of `tabstop` is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.set_shiftwidth(4);
+    /// buffer.update_from_string("<gt><gt><gt><gt>").unwrap();
+    /// assert_eq!(buffer.as_content(), "\tabc");
+    /// buffer.update_from_string("<lt><lt>").unwrap();
+    /// assert_eq!(buffer.as_content(), "    abc");
+    /// ```
+    pub const fn set_shiftwidth(&mut self, shiftwidth: usize) {
+        self.options.shiftwidth = shiftwidth;
+    }
+
+    /// Sets the number of columns a tab counts for, like vim's `tabstop`
+    /// option.
+    ///
+    /// It is 8 by default. Without `expandtab`, indents are made of as many
+    /// tabs as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.set_tabstop(4);
+    /// buffer.set_shiftwidth(0);
+    /// buffer.update_from_string("<gt><gt>").unwrap();
+    /// assert_eq!(buffer.as_content(), "\tabc");
+    /// ```
+    pub const fn set_tabstop(&mut self, tabstop: usize) {
+        self.options.tabstop = tabstop;
+    }
+
+    /// Sets the maximum width of the lines, used by `gq`, `gw` and auto-wrap,
+    /// like vim's `textwidth` option.
+    ///
+    /// It is `0` by default: `gq` then formats to 79 columns, and lines are
+    /// never wrapped while typing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("# one two three four");
+    /// buffer.set_textwidth(12);
+    /// buffer.update_from_string("gqq").unwrap();
+    /// assert_eq!(buffer.as_content(), "# one two\n# three four");
+    /// ```
+    pub const fn set_textwidth(&mut self, textwidth: usize) {
+        self.options.textwidth = textwidth;
+    }
+
+    /// Returns the error of the last filter that failed, if any, and forgets
+    /// it.
+    ///
+    /// When a filter fails, the buffer is left unchanged and the keypress
+    /// that ran it is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, FilterError};
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer.update_from_string("!!sort<CR>").unwrap();
+    /// assert_eq!(buffer.as_content(), "abc");
+    /// assert!(matches!(buffer.take_filter_error(), Some(FilterError::NoFilter)));
+    /// assert!(buffer.take_filter_error().is_none());
+    /// ```
+    pub const fn take_filter_error(&mut self) -> Option<FilterError> {
+        self.filter_error.take()
+    }
+
+    /// Shares the registers of the buffer with other buffers, that may live
+    /// on other threads.
+    ///
+    /// The previous registers of the buffer are dropped. See
+    /// [`SharedRegisters`] for an example.
+    pub fn use_shared_registers(&mut self, registers: &SharedRegisters) {
+        self.registers = registers.into();
+    }
 }
 
 impl From<String> for Buffer {
@@ -0,0 +1,305 @@
+use core::fmt;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::utils::base64;
+
+/// Source of the content of the clipboard registers `+` and `*`.
+///
+/// Install one on a [`crate::Buffer`] with
+/// [`crate::Buffer::set_clipboard_provider`] to give access to the system
+/// clipboard. Providers are [`Send`] as they live in the registers, which
+/// can be shared between threads.
+pub trait ClipboardProvider: fmt::Debug + Send {
+    /// Returns the content of the selection, or `None` if it can't be read.
+    fn get(&mut self, selection: ClipboardSelection) -> Option<String>;
+
+    /// Replaces the content of the selection.
+    ///
+    /// Returns `false` if the selection couldn't be written.
+    fn set(&mut self, selection: ClipboardSelection, content: &str) -> bool;
+}
+
+/// Behaviour of the unnamed register regarding the clipboard, like vim's
+/// `clipboard` option.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardOption {
+    /// The unnamed register is independent from the clipboard.
+    #[default]
+    Off,
+    /// The unnamed register mirrors the `*` register, like
+    /// `clipboard=unnamed`.
+    Unnamed,
+    /// The unnamed register mirrors the `+` register, like
+    /// `clipboard=unnamedplus`.
+    UnnamedPlus,
+}
+
+impl ClipboardOption {
+    /// Returns the selection mirrored by the unnamed register, if any.
+    pub(super) const fn as_selection(self) -> Option<ClipboardSelection> {
+        match self {
+            Self::Off => None,
+            Self::Unnamed => Some(ClipboardSelection::Primary),
+            Self::UnnamedPlus => Some(ClipboardSelection::Clipboard),
+        }
+    }
+}
+
+/// Selection targeted by a clipboard register.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardSelection {
+    /// System clipboard, accessed with the `+` register
+    Clipboard,
+    /// X11 primary selection, accessed with the `*` register
+    Primary,
+}
+
+impl ClipboardSelection {
+    /// Returns the register that accesses this selection.
+    pub(super) const fn as_register(self) -> char {
+        match self {
+            Self::Clipboard => '+',
+            Self::Primary => '*',
+        }
+    }
+
+    /// Returns the selection accessed by this register, if it is a clipboard
+    /// register.
+    pub(super) const fn maybe_from(reg: char) -> Option<Self> {
+        match reg {
+            '+' => Some(Self::Clipboard),
+            '*' => Some(Self::Primary),
+            _ => None,
+        }
+    }
+}
+
+/// Clipboard provider that shells out to a clipboard tool: `wl-copy` and
+/// `wl-paste` on Wayland, or `xclip` or `xsel` on X11.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vim_buffer::{Buffer, CommandProvider};
+///
+/// let mut buffer = Buffer::default();
+/// if let Some(provider) = CommandProvider::detect() {
+///     buffer.set_clipboard_provider(provider);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandProvider(ClipboardTool);
+
+impl CommandProvider {
+    /// Returns a provider for the first clipboard tool found in the `PATH`.
+    ///
+    /// The Wayland tools are preferred when `WAYLAND_DISPLAY` is set.
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        let wayland = env::var_os("WAYLAND_DISPLAY").is_some();
+        [ClipboardTool::WlClipboard, ClipboardTool::Xclip, ClipboardTool::Xsel]
+            .into_iter()
+            .filter(|tool| wayland || *tool != ClipboardTool::WlClipboard)
+            .find(|tool| is_in_path(tool.as_program(false)))
+            .map(Self)
+    }
+}
+
+impl ClipboardProvider for CommandProvider {
+    fn get(&mut self, selection: ClipboardSelection) -> Option<String> {
+        let output = Command::new(self.0.as_program(false))
+            .args(self.0.as_args(selection, false))
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    }
+
+    fn set(&mut self, selection: ClipboardSelection, content: &str) -> bool {
+        let Ok(mut child) = Command::new(self.0.as_program(true))
+            .args(self.0.as_args(selection, true))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        let written = child.stdin.take().is_some_and(|mut stdin| {
+            stdin.write_all(content.as_bytes()).is_ok()
+        });
+        child.wait().is_ok_and(|status| status.success()) && written
+    }
+}
+
+/// External tool used to access the system clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClipboardTool {
+    /// `wl-copy` and `wl-paste`, on Wayland
+    WlClipboard,
+    /// `xclip`, on X11
+    Xclip,
+    /// `xsel`, on X11
+    Xsel,
+}
+
+impl ClipboardTool {
+    /// Returns the arguments to pass to the program to read or write the
+    /// selection.
+    const fn as_args(
+        self,
+        selection: ClipboardSelection,
+        write: bool,
+    ) -> &'static [&'static str] {
+        match (self, selection, write) {
+            (Self::WlClipboard, ClipboardSelection::Clipboard, true) => &[],
+            (Self::WlClipboard, ClipboardSelection::Clipboard, false) =>
+                &["--no-newline"],
+            (Self::WlClipboard, ClipboardSelection::Primary, true) =>
+                &["--primary"],
+            (Self::WlClipboard, ClipboardSelection::Primary, false) =>
+                &["--primary", "--no-newline"],
+            (Self::Xclip, ClipboardSelection::Clipboard, true) =>
+                &["-selection", "clipboard", "-in"],
+            (Self::Xclip, ClipboardSelection::Clipboard, false) =>
+                &["-selection", "clipboard", "-out"],
+            (Self::Xclip, ClipboardSelection::Primary, true) =>
+                &["-selection", "primary", "-in"],
+            (Self::Xclip, ClipboardSelection::Primary, false) =>
+                &["-selection", "primary", "-out"],
+            (Self::Xsel, ClipboardSelection::Clipboard, true) =>
+                &["--clipboard", "--input"],
+            (Self::Xsel, ClipboardSelection::Clipboard, false) =>
+                &["--clipboard", "--output"],
+            (Self::Xsel, ClipboardSelection::Primary, true) =>
+                &["--primary", "--input"],
+            (Self::Xsel, ClipboardSelection::Primary, false) =>
+                &["--primary", "--output"],
+        }
+    }
+
+    /// Returns the name of the program to run to read or write the clipboard.
+    const fn as_program(self, write: bool) -> &'static str {
+        match (self, write) {
+            (Self::WlClipboard, true) => "wl-copy",
+            (Self::WlClipboard, false) => "wl-paste",
+            (Self::Xclip, _) => "xclip",
+            (Self::Xsel, _) => "xsel",
+        }
+    }
+}
+
+/// Clipboard provider that keeps the selections in memory.
+///
+/// This is useful for tests, or to share a clipboard between buffers without
+/// accessing the system clipboard.
+///
+/// # Examples
+///
+/// ```
+/// use vim_buffer::{Buffer, MemoryProvider};
+///
+/// let mut buffer = Buffer::from("abc");
+/// buffer.set_clipboard_provider(MemoryProvider::default());
+/// buffer.update_from_string("\"+yy\"+p").unwrap();
+/// assert_eq!(buffer.as_content(), "abc\nabc");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemoryProvider {
+    /// Content of the `+` register
+    clipboard: Option<String>,
+    /// Content of the `*` register
+    primary: Option<String>,
+}
+
+impl MemoryProvider {
+    /// Returns the content stored for the given selection.
+    const fn as_selection_mut(
+        &mut self,
+        selection: ClipboardSelection,
+    ) -> &mut Option<String> {
+        match selection {
+            ClipboardSelection::Clipboard => &mut self.clipboard,
+            ClipboardSelection::Primary => &mut self.primary,
+        }
+    }
+}
+
+impl ClipboardProvider for MemoryProvider {
+    fn get(&mut self, selection: ClipboardSelection) -> Option<String> {
+        self.as_selection_mut(selection).clone()
+    }
+
+    fn set(&mut self, selection: ClipboardSelection, content: &str) -> bool {
+        *self.as_selection_mut(selection) = Some(content.to_owned());
+        true
+    }
+}
+
+/// Writer of OSC 52 escape sequences, that ask the terminal to set its
+/// clipboard.
+///
+/// This gives access to the clipboard of the terminal emulator, even when the
+/// buffer runs on a remote machine, through SSH for example.
+pub(super) struct Osc52 {
+    /// Maximum length of the base64 payload of a sequence
+    max_len: usize,
+    /// Destination of the sequences, usually the terminal
+    writer: Box<dyn Write + Send>,
+}
+
+impl Osc52 {
+    /// Creates a writer of OSC 52 sequences, that won't send payloads longer
+    /// than `max_len` bytes once encoded.
+    pub(super) fn new(writer: Box<dyn Write + Send>, max_len: usize) -> Self {
+        Self { max_len, writer }
+    }
+
+    /// Writes the sequence that sets the selection to `content`.
+    ///
+    /// The sequence is skipped if the encoded content is too long. As the
+    /// terminal never acknowledges it, sending is best effort: write errors
+    /// are ignored.
+    pub(super) fn send(
+        &mut self,
+        selection: ClipboardSelection,
+        content: &str,
+    ) {
+        let payload = base64::encode(content.as_bytes());
+        if payload.len() > self.max_len {
+            return;
+        }
+        let target = match selection {
+            ClipboardSelection::Clipboard => 'c',
+            ClipboardSelection::Primary => 'p',
+        };
+        drop(
+            write!(self.writer, "\x1b]52;{target};{payload}\x07")
+                .and_then(|()| self.writer.flush()),
+        );
+    }
+}
+
+impl fmt::Debug for Osc52 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Osc52")
+            .field("max_len", &self.max_len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Checks if an executable with the given name is in one of the directories
+/// of the `PATH`.
+fn is_in_path(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| dir.join(program).is_file())
+    })
+}
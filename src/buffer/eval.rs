@@ -0,0 +1,732 @@
+use core::fmt;
+
+/// Number of significant digits used to display floats, like vim's `%g`
+const FLOAT_PRECISION: i32 = 6;
+
+/// Maximum nesting of parentheses, operators and function calls, so that
+/// deeply nested expressions don't overflow the stack
+const MAX_DEPTH: usize = 100;
+
+/// Maximum length in bytes of the strings built by an expression
+const MAX_LEN: usize = 1 << 20;
+
+/// Builtin functions that can be called in expressions
+const FUNCTIONS: [&str; 9] = [
+    "abs", "float2nr", "repeat", "round", "str2nr", "strlen", "tolower",
+    "toupper", "trim",
+];
+
+/// Errors that may occur when evaluating an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// A number was divided by zero.
+    DivisionByZero,
+    /// A function was called with the wrong number of arguments.
+    InvalidArguments(String),
+    /// An operator was applied to a value of the wrong type.
+    InvalidOperand,
+    /// The expression is not valid.
+    Syntax,
+    /// The expression is nested more than [`MAX_DEPTH`] times.
+    TooDeep,
+    /// A string built by the expression is longer than [`MAX_LEN`].
+    TooLong,
+    /// The function doesn't exist or isn't supported.
+    UnknownFunction(String),
+}
+
+/// Value of a Vimscript expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Floating point number, like `1.5`
+    Float(f64),
+    /// Integer, like `42`
+    Number(i64),
+    /// String, like `"abc"` or `'abc'`
+    String(String),
+}
+
+impl Value {
+    /// Converts the value to a float, for arithmetic with a float.
+    #[expect(
+        clippy::as_conversions,
+        clippy::cast_precision_loss,
+        reason = "vim"
+    )]
+    fn to_float(&self) -> f64 {
+        match self {
+            Self::Float(float) => *float,
+            Self::Number(_) | Self::String(_) => self.to_number() as f64,
+        }
+    }
+
+    /// Converts the value to a number, like vim does for arithmetic.
+    ///
+    /// Strings are converted with their leading digits, and are 0 if they
+    /// don't start with a number. Floats are truncated.
+    #[expect(clippy::as_conversions, reason = "truncation is wanted")]
+    #[expect(clippy::cast_possible_truncation, reason = "truncation is wanted")]
+    fn to_number(&self) -> i64 {
+        match self {
+            Self::Float(float) => *float as i64,
+            Self::Number(number) => *number,
+            Self::String(string) => str_to_number(string),
+        }
+    }
+
+    /// Returns `true` if the value is considered true in a condition.
+    fn to_truth(&self) -> bool {
+        match self {
+            Self::Float(float) => *float != 0.0,
+            Self::Number(_) | Self::String(_) => self.to_number() != 0,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(float) => format_float(*float).fmt(f),
+            Self::Number(number) => number.fmt(f),
+            Self::String(string) => string.fmt(f),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+/// Recursive descent parser that evaluates an expression while parsing it
+struct Parser {
+    /// Chars of the expression
+    chars: Vec<char>,
+    /// Number of nested expressions being parsed, see [`Self::nested`]
+    depth: usize,
+    /// Index of the next char to read
+    pos: usize,
+}
+
+#[expect(
+    clippy::missing_errors_doc,
+    reason = "every step fails on an invalid expression"
+)]
+impl Parser {
+    /// Parses `+`, `-` and the string concatenation `.` or `..`.
+    fn additive(&mut self) -> Result<Value, EvalError> {
+        let mut value = self.multiplicative()?;
+        loop {
+            self.skip_spaces();
+            if self.eat("..") || self.eat(".") {
+                let rhs = self.multiplicative()?;
+                value = concat(&value, &rhs)?;
+            } else if self.eat("+") {
+                value = arithmetic(&value, &self.multiplicative()?, '+')?;
+            } else if self.eat("-") {
+                value = arithmetic(&value, &self.multiplicative()?, '-')?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Parses the arguments of a function call, after the opening
+    /// parenthesis, and calls the function.
+    fn call(&mut self, name: &str) -> Result<Value, EvalError> {
+        let mut args = vec![];
+        self.skip_spaces();
+        if self.eat(")") {
+            return call_function(name, &args);
+        }
+        loop {
+            args.push(self.nested(Self::ternary)?);
+            self.skip_spaces();
+            if self.eat(")") {
+                return call_function(name, &args);
+            }
+            if !self.eat(",") {
+                return Err(EvalError::Syntax);
+            }
+        }
+    }
+
+    /// Parses the comparison operators, like `==`, `<` or `!=?`.
+    ///
+    /// Comparisons can't be chained.
+    fn comparison(&mut self) -> Result<Value, EvalError> {
+        let lhs = self.additive()?;
+        self.skip_spaces();
+        let Some(op) = ["==", "!=", ">=", "<=", ">", "<"]
+            .into_iter()
+            .find(|op| self.eat(op))
+        else {
+            return Ok(lhs);
+        };
+        let ignore_case = if self.eat("?") {
+            true
+        } else {
+            self.eat("#");
+            false
+        };
+        let rhs = self.additive()?;
+        let ordering = match (&lhs, &rhs) {
+            (Value::String(left), Value::String(right)) if ignore_case =>
+                left.to_lowercase().cmp(&right.to_lowercase()),
+            (Value::String(left), Value::String(right)) => left.cmp(right),
+            (Value::Float(_), _) | (_, Value::Float(_)) => lhs
+                .to_float()
+                .partial_cmp(&rhs.to_float())
+                .ok_or(EvalError::InvalidOperand)?,
+            _ => lhs.to_number().cmp(&rhs.to_number()),
+        };
+        Ok(Value::from(match op {
+            "==" => ordering.is_eq(),
+            "!=" => ordering.is_ne(),
+            ">=" => ordering.is_ge(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_lt(),
+        }))
+    }
+
+    /// Parses a string between double quotes, with backslash escapes.
+    fn double_quoted(&mut self) -> Result<Value, EvalError> {
+        let mut string = String::new();
+        loop {
+            let ch = self.next().ok_or(EvalError::Syntax)?;
+            match ch {
+                '"' => return Ok(Value::String(string)),
+                '\\' => string.push(match self.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('e') => '\x1b',
+                    Some(escaped) => escaped,
+                    None => return Err(EvalError::Syntax),
+                }),
+                _ => string.push(ch),
+            }
+        }
+    }
+
+    /// Consumes `token` if the expression continues with it.
+    fn eat(&mut self, token: &str) -> bool {
+        let len = token.chars().count();
+        let end = self.pos.saturating_add(len);
+        if self
+            .chars
+            .get(self.pos..end)
+            .is_some_and(|next| next.iter().copied().eq(token.chars()))
+        {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses a function name.
+    fn identifier(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(ch) = self.peek()
+            && (ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            name.push(ch);
+            self.pos = self.pos.saturating_add(1);
+        }
+        name
+    }
+
+    /// Parses the logical and operator `&&`.
+    fn logical_and(&mut self) -> Result<Value, EvalError> {
+        let mut value = self.comparison()?;
+        loop {
+            self.skip_spaces();
+            if !self.eat("&&") {
+                return Ok(value);
+            }
+            let rhs = self.comparison()?;
+            value = Value::from(value.to_truth() && rhs.to_truth());
+        }
+    }
+
+    /// Parses the logical or operator `||`.
+    fn logical_or(&mut self) -> Result<Value, EvalError> {
+        let mut value = self.logical_and()?;
+        loop {
+            self.skip_spaces();
+            if !self.eat("||") {
+                return Ok(value);
+            }
+            let rhs = self.logical_and()?;
+            value = Value::from(value.to_truth() || rhs.to_truth());
+        }
+    }
+
+    /// Parses `*`, `/` and `%`.
+    fn multiplicative(&mut self) -> Result<Value, EvalError> {
+        let mut value = self.unary()?;
+        loop {
+            self.skip_spaces();
+            let Some(op) = ['*', '/', '%']
+                .into_iter()
+                .find(|op| self.eat(&op.to_string()))
+            else {
+                return Ok(value);
+            };
+            value = arithmetic(&value, &self.unary()?, op)?;
+        }
+    }
+
+    /// Parses a nested expression with `parse`, like the one between
+    /// parentheses.
+    ///
+    /// Fails if the expression is nested too deeply.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Value, EvalError>,
+    ) -> Result<Value, EvalError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(EvalError::TooDeep);
+        }
+        self.depth = self.depth.saturating_add(1);
+        let value = parse(self);
+        self.depth = self.depth.saturating_sub(1);
+        value
+    }
+
+    /// Returns the next char, and moves after it.
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos = self.pos.saturating_add(1);
+        Some(ch)
+    }
+
+    /// Parses a decimal or hexadecimal number, or a float.
+    fn number(&mut self) -> Result<Value, EvalError> {
+        if self.eat("0x") || self.eat("0X") {
+            let digits = self.take_while(|ch| ch.is_ascii_hexdigit());
+            return i64::from_str_radix(&digits, 16)
+                .ok()
+                .map(Value::Number)
+                .ok_or(EvalError::Syntax);
+        }
+        let mut literal = self.take_while(|ch| ch.is_ascii_digit());
+        let is_float = self.peek() == Some('.')
+            && self
+                .chars
+                .get(self.pos.saturating_add(1))
+                .is_some_and(char::is_ascii_digit);
+        if !is_float {
+            return literal
+                .parse()
+                .ok()
+                .map(Value::Number)
+                .ok_or(EvalError::Syntax);
+        }
+        self.pos = self.pos.saturating_add(1);
+        literal.push('.');
+        literal.push_str(&self.take_while(|ch| ch.is_ascii_digit()));
+        if let Some(exp @ ('e' | 'E')) = self.peek() {
+            self.pos = self.pos.saturating_add(1);
+            literal.push(exp);
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                self.pos = self.pos.saturating_add(1);
+                literal.push(sign);
+            }
+            literal.push_str(&self.take_while(|ch| ch.is_ascii_digit()));
+        }
+        literal.parse().ok().map(Value::Float).ok_or(EvalError::Syntax)
+    }
+
+    /// Returns the next char, without moving.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Parses literals, function calls and parenthesised expressions.
+    fn primary(&mut self) -> Result<Value, EvalError> {
+        self.skip_spaces();
+        match self.peek().ok_or(EvalError::Syntax)? {
+            '(' => {
+                self.pos = self.pos.saturating_add(1);
+                let value = self.nested(Self::ternary)?;
+                self.skip_spaces();
+                if self.eat(")") { Ok(value) } else { Err(EvalError::Syntax) }
+            }
+            '"' => {
+                self.pos = self.pos.saturating_add(1);
+                self.double_quoted()
+            }
+            '\'' => {
+                self.pos = self.pos.saturating_add(1);
+                self.single_quoted()
+            }
+            '0'..='9' => self.number(),
+            ch if ch.is_ascii_alphabetic() || ch == '_' => {
+                let name = self.identifier();
+                self.skip_spaces();
+                if self.eat("(") {
+                    self.call(&name)
+                } else {
+                    Err(EvalError::Syntax)
+                }
+            }
+            _ => Err(EvalError::Syntax),
+        }
+    }
+
+    /// Parses a literal string between single quotes, where `''` is a quote.
+    fn single_quoted(&mut self) -> Result<Value, EvalError> {
+        let mut string = String::new();
+        loop {
+            match self.next().ok_or(EvalError::Syntax)? {
+                '\'' if self.eat("'") => string.push('\''),
+                '\'' => return Ok(Value::String(string)),
+                ch => string.push(ch),
+            }
+        }
+    }
+
+    /// Moves after the spaces and tabs.
+    fn skip_spaces(&mut self) {
+        self.take_while(|ch| ch == ' ' || ch == '\t');
+    }
+
+    /// Returns the chars that satisfy `predicate`, and moves after them.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some(ch) = self.peek()
+            && predicate(ch)
+        {
+            taken.push(ch);
+            self.pos = self.pos.saturating_add(1);
+        }
+        taken
+    }
+
+    /// Parses the ternary operator `cond ? then : else`.
+    fn ternary(&mut self) -> Result<Value, EvalError> {
+        let cond = self.logical_or()?;
+        self.skip_spaces();
+        if !self.eat("?") {
+            return Ok(cond);
+        }
+        let then = self.nested(Self::ternary)?;
+        self.skip_spaces();
+        if !self.eat(":") {
+            return Err(EvalError::Syntax);
+        }
+        let otherwise = self.nested(Self::ternary)?;
+        Ok(if cond.to_truth() { then } else { otherwise })
+    }
+
+    /// Parses the unary operators `!`, `-` and `+`.
+    fn unary(&mut self) -> Result<Value, EvalError> {
+        self.skip_spaces();
+        if self.eat("!") {
+            Ok(Value::from(!self.nested(Self::unary)?.to_truth()))
+        } else if self.eat("-") {
+            #[expect(clippy::float_arithmetic, reason = "vim floats")]
+            Ok(match self.nested(Self::unary)? {
+                Value::Float(float) => Value::Float(-float),
+                value @ (Value::Number(_) | Value::String(_)) =>
+                    Value::Number(value.to_number().wrapping_neg()),
+            })
+        } else if self.eat("+") {
+            Ok(match self.nested(Self::unary)? {
+                float @ Value::Float(_) => float,
+                value @ (Value::Number(_) | Value::String(_)) =>
+                    Value::Number(value.to_number()),
+            })
+        } else {
+            self.primary()
+        }
+    }
+}
+
+/// Applies an arithmetic operator on two values.
+///
+/// The result is a float if one of the operands is a float, and a number
+/// otherwise. Strings are converted to numbers.
+///
+/// # Errors
+///
+/// Returns an error when dividing a number by zero, or when using `%` on
+/// floats.
+#[expect(clippy::float_arithmetic, reason = "vim floats")]
+fn arithmetic(lhs: &Value, rhs: &Value, op: char) -> Result<Value, EvalError> {
+    if matches!(lhs, Value::Float(_)) || matches!(rhs, Value::Float(_)) {
+        let (left, right) = (lhs.to_float(), rhs.to_float());
+        return Ok(Value::Float(match op {
+            '+' => left + right,
+            '-' => left - right,
+            '*' => left * right,
+            '/' => left / right,
+            _ => return Err(EvalError::InvalidOperand),
+        }));
+    }
+    let (left, right) = (lhs.to_number(), rhs.to_number());
+    Ok(Value::Number(match op {
+        '+' => left.wrapping_add(right),
+        '-' => left.wrapping_sub(right),
+        '*' => left.wrapping_mul(right),
+        '/' => left.checked_div(right).ok_or(EvalError::DivisionByZero)?,
+        _ => left.checked_rem(right).ok_or(EvalError::DivisionByZero)?,
+    }))
+}
+
+/// Calls one of the supported builtin functions.
+///
+/// # Errors
+///
+/// Returns an error if the function doesn't exist, or if it isn't called with
+/// the right number of arguments.
+fn call_function(name: &str, args: &[Value]) -> Result<Value, EvalError> {
+    let invalid = || EvalError::InvalidArguments(name.to_owned());
+    match (name, args) {
+        ("abs", [Value::Float(float)]) => Ok(Value::Float(float.abs())),
+        ("abs", [value]) => Ok(Value::Number(value.to_number().wrapping_abs())),
+        ("float2nr" | "str2nr", [value]) =>
+            Ok(Value::Number(value.to_number())),
+        ("repeat", [value, times]) => {
+            let string = value.to_string();
+            let count = usize::try_from(times.to_number()).unwrap_or(0);
+            let len = string.len().saturating_mul(count);
+            if len > MAX_LEN {
+                return Err(EvalError::TooLong);
+            }
+            Ok(Value::String(string.repeat(count)))
+        }
+        ("round", [value]) => Ok(Value::Float(value.to_float().round())),
+        ("strlen", [value]) => i64::try_from(value.to_string().len())
+            .ok()
+            .map(Value::Number)
+            .ok_or_else(invalid),
+        ("tolower", [value]) =>
+            Ok(Value::String(value.to_string().to_lowercase())),
+        ("toupper", [value]) =>
+            Ok(Value::String(value.to_string().to_uppercase())),
+        ("trim", [value]) =>
+            Ok(Value::String(value.to_string().trim().to_owned())),
+        _ if FUNCTIONS.contains(&name) => Err(invalid()),
+        _ => Err(EvalError::UnknownFunction(name.to_owned())),
+    }
+}
+
+/// Concatenates two values as strings, like `.` and `..`.
+///
+/// # Errors
+///
+/// Returns an error if the result is longer than [`MAX_LEN`].
+fn concat(lhs: &Value, rhs: &Value) -> Result<Value, EvalError> {
+    let (left, right) = (lhs.to_string(), rhs.to_string());
+    if left.len().saturating_add(right.len()) > MAX_LEN {
+        return Err(EvalError::TooLong);
+    }
+    Ok(Value::String(format!("{left}{right}")))
+}
+
+/// Evaluates a Vimscript expression.
+///
+/// Only a safe subset of Vimscript is supported: integer and float
+/// arithmetic, string concatenation with `.` and `..`, comparisons, logical
+/// operators, the ternary operator and a few builtin functions.
+///
+/// # Errors
+///
+/// Returns an error if the expression is not valid, if it can't be computed,
+/// or if it is nested too deeply or builds too long a string.
+pub fn evaluate(expr: &str) -> Result<Value, EvalError> {
+    let mut parser = Parser { chars: expr.chars().collect(), depth: 0, pos: 0 };
+    let value = parser.ternary()?;
+    parser.skip_spaces();
+    if parser.pos == parser.chars.len() {
+        Ok(value)
+    } else {
+        Err(EvalError::Syntax)
+    }
+}
+
+/// Formats a float like vim does, with 6 significant digits.
+#[expect(clippy::as_conversions, reason = "exponent is small")]
+#[expect(clippy::cast_possible_truncation, reason = "exponent is small")]
+fn format_float(float: f64) -> String {
+    if float.is_nan() {
+        return "nan".to_owned();
+    }
+    if float.is_infinite() {
+        return if float > 0.0 { "inf" } else { "-inf" }.to_owned();
+    }
+    let exponent: i32 =
+        if float == 0.0 { 0 } else { float.abs().log10().floor() as i32 };
+    if (-4..FLOAT_PRECISION).contains(&exponent) {
+        let decimals = usize::try_from(
+            FLOAT_PRECISION.saturating_sub(1).saturating_sub(exponent),
+        )
+        .unwrap_or(0);
+        let formatted = format!("{float:.decimals$}");
+        let trimmed = if formatted.contains('.') {
+            formatted.trim_end_matches('0')
+        } else {
+            &formatted
+        };
+        trimmed.strip_suffix('.').map_or_else(
+            || {
+                if trimmed.contains('.') {
+                    trimmed.to_owned()
+                } else {
+                    format!("{trimmed}.0")
+                }
+            },
+            |integer| format!("{integer}.0"),
+        )
+    } else {
+        let decimals =
+            usize::try_from(FLOAT_PRECISION.saturating_sub(1)).unwrap_or(0);
+        let formatted = format!("{float:.decimals$e}");
+        let (digits, exp) =
+            formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = digits.trim_end_matches('0');
+        if mantissa.ends_with('.') {
+            format!("{mantissa}0e{exp}")
+        } else {
+            format!("{mantissa}e{exp}")
+        }
+    }
+}
+
+/// Converts a string to a number with its leading digits, like `str2nr`.
+fn str_to_number(string: &str) -> i64 {
+    let trimmed = string.trim_start();
+    let (negative, digits) =
+        trimmed.strip_prefix('-').map_or((false, trimmed), |rest| (true, rest));
+    let number = digits
+        .chars()
+        .map_while(|ch| ch.to_digit(10))
+        .fold(i64::default(), |number, digit| {
+            number.wrapping_mul(10).wrapping_add(i64::from(digit))
+        });
+    if negative { number.wrapping_neg() } else { number }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::eval::{EvalError, evaluate};
+
+    fn eval(expr: &str) -> String {
+        evaluate(expr).unwrap().to_string()
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * 3"), "9");
+        assert_eq!(eval("7 / 2"), "3");
+        assert_eq!(eval("7 % 4"), "3");
+        assert_eq!(eval("-3 - -4"), "1");
+        assert_eq!(eval("0x1f + 1"), "32");
+        assert_eq!(eval("'12abc' + 1"), "13");
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(eval("1.5 * 2"), "3.0");
+        assert_eq!(eval("1.0 / 3"), "0.333333");
+        assert_eq!(eval("7 / 2.0"), "3.5");
+        assert_eq!(eval("1.5e3"), "1500.0");
+        assert_eq!(eval("2.5e10"), "2.5e10");
+        assert_eq!(eval("100000.0"), "100000.0");
+        assert_eq!(eval("1.0e-5"), "1.0e-5");
+        assert_eq!(eval("1.0 / 0"), "inf");
+        assert_eq!(evaluate("1.5 % 2"), Err(EvalError::InvalidOperand));
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(eval(r#""abc" . 'def'"#), "abcdef");
+        assert_eq!(eval("'a' .. (1 + 2)"), "a3");
+        assert_eq!(eval("'a' .. 1 + 2"), "2");
+        assert_eq!(eval("1 . 2"), "12");
+        assert_eq!(eval(r#""a\"b\\c""#), r#"a"b\c"#);
+        assert_eq!(eval("'it''s'"), "it's");
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(eval("1 < 2"), "1");
+        assert_eq!(eval("2 <= 1"), "0");
+        assert_eq!(eval("'abc' == 'abc'"), "1");
+        assert_eq!(eval("'abc' == 'ABC'"), "0");
+        assert_eq!(eval("'abc' ==? 'ABC'"), "1");
+        assert_eq!(eval("1.5 > 1"), "1");
+        assert_eq!(eval("1 != 1 || 2 > 1 && 3"), "1");
+        assert_eq!(eval("!0"), "1");
+        assert_eq!(eval("1 > 2 ? 'yes' : 'no'"), "no");
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(eval("strlen('hello')"), "5");
+        assert_eq!(eval("toupper('abc') . tolower('DEF')"), "ABCdef");
+        assert_eq!(eval("repeat('ab', 3)"), "ababab");
+        assert_eq!(eval("repeat('ab', -1)"), "");
+        assert_eq!(eval("abs(-4) + float2nr(2.7)"), "6");
+        assert_eq!(eval("round(2.5)"), "3.0");
+        assert_eq!(eval("trim('  a ')"), "a");
+        assert_eq!(eval("str2nr('42')"), "42");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate("1 / 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(evaluate("1 +"), Err(EvalError::Syntax));
+        assert_eq!(evaluate("(1"), Err(EvalError::Syntax));
+        assert_eq!(evaluate("1 2"), Err(EvalError::Syntax));
+        assert_eq!(evaluate("'abc"), Err(EvalError::Syntax));
+        assert_eq!(
+            evaluate("system('ls')"),
+            Err(EvalError::UnknownFunction("system".to_owned()))
+        );
+        assert_eq!(
+            evaluate("strlen()"),
+            Err(EvalError::InvalidArguments("strlen".to_owned()))
+        );
+    }
+
+    #[test]
+    fn nesting_limit() {
+        assert_eq!(
+            eval(&format!("{}1{}", "(".repeat(50), ")".repeat(50))),
+            "1"
+        );
+        let parens = format!("{}1{}", "(".repeat(1000), ")".repeat(1000));
+        assert_eq!(evaluate(&parens), Err(EvalError::TooDeep));
+        assert_eq!(
+            evaluate(&format!("{}1", "!".repeat(1000))),
+            Err(EvalError::TooDeep)
+        );
+        assert_eq!(
+            evaluate(&format!("{}1", "-".repeat(1000))),
+            Err(EvalError::TooDeep)
+        );
+        let calls = format!("{}1{}", "abs(".repeat(1000), ")".repeat(1000));
+        assert_eq!(evaluate(&calls), Err(EvalError::TooDeep));
+    }
+
+    #[test]
+    fn length_limit() {
+        assert_eq!(eval("strlen(repeat('a', 1000))"), "1000");
+        assert_eq!(evaluate("repeat('ab', 1000000)"), Err(EvalError::TooLong));
+        assert_eq!(
+            evaluate("repeat('a', 9223372036854775807)"),
+            Err(EvalError::TooLong)
+        );
+        let big = "repeat('a', 1000000)";
+        assert_eq!(
+            evaluate(&format!("{big} . {big}")),
+            Err(EvalError::TooLong)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use core::error::Error;
+use core::fmt;
+use std::io::{self, Write as _};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Filter used by `!{motion}` and `!!`, that replaces lines by the output of
+/// a command.
+///
+/// Install one on a [`crate::Buffer`] with [`crate::Buffer::set_filter`].
+/// Closures that take the command and the lines, and return the new lines,
+/// are filters. [`CommandFilter`] runs the command in a shell.
+///
+/// Filters must be [`Send`], so that a buffer holding one can still be moved
+/// to another thread.
+///
+/// # Examples
+///
+/// ```
+/// use vim_buffer::{Buffer, FilterError};
+///
+/// let mut buffer = Buffer::from("b\na");
+/// buffer.set_filter(|command: &str, input: &str| match command {
+///     "sort" => {
+///         let mut lines = input.lines().collect::<Vec<_>>();
+///         lines.sort_unstable();
+///         Ok(lines.join("\n"))
+///     }
+///     _ => Err(FilterError::Message(format!("{command}: not found"))),
+/// });
+/// buffer.update_from_string("2!!sort<CR>").unwrap();
+/// assert_eq!(buffer.as_content(), "a\nb");
+/// ```
+pub trait FilterProvider: Send {
+    /// Runs `command` with `input` as its standard input, and returns its
+    /// output.
+    ///
+    /// The input holds whole lines, each ended by a newline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command failed, in which case the buffer is
+    /// left unchanged.
+    fn filter(
+        &mut self,
+        command: &str,
+        input: &str,
+    ) -> Result<String, FilterError>;
+}
+
+impl<F> FilterProvider for F
+where F: FnMut(&str, &str) -> Result<String, FilterError> + Send
+{
+    fn filter(
+        &mut self,
+        command: &str,
+        input: &str,
+    ) -> Result<String, FilterError> {
+        self(command, input)
+    }
+}
+
+impl fmt::Debug for dyn FilterProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilterProvider").finish_non_exhaustive()
+    }
+}
+
+/// Filter that runs the command in a shell, like vim does.
+///
+/// The command is run with `sh -c` by default: it has access to the whole
+/// system, so only install this filter if the keymaps are trusted.
+///
+/// # Examples
+///
+/// ```no_run
+/// use vim_buffer::{Buffer, CommandFilter};
+///
+/// let mut buffer = Buffer::from("b\na");
+/// buffer.set_filter(CommandFilter::default());
+/// buffer.update_from_string("2!!sort<CR>").unwrap();
+/// assert_eq!(buffer.as_content(), "a\nb");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandFilter {
+    /// Shell that runs the commands, with `-c`
+    shell: String,
+}
+
+impl CommandFilter {
+    /// Returns a filter that runs the commands with `shell -c`.
+    #[must_use]
+    pub fn new(shell: &str) -> Self {
+        Self { shell: shell.to_owned() }
+    }
+}
+
+impl Default for CommandFilter {
+    fn default() -> Self {
+        Self::new("sh")
+    }
+}
+
+impl FilterProvider for CommandFilter {
+    fn filter(
+        &mut self,
+        command: &str,
+        input: &str,
+    ) -> Result<String, FilterError> {
+        let mut child = Command::new(&self.shell)
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // Written from another thread, so that a command that outputs a lot
+        // before reading all its input doesn't block.
+        let writer = child.stdin.take().map(|mut stdin| {
+            let owned = input.to_owned();
+            thread::spawn(move || stdin.write_all(owned.as_bytes()))
+        });
+        let output = child.wait_with_output()?;
+        if let Some(Ok(Err(err))) = writer.map(thread::JoinHandle::join)
+            && err.kind() != io::ErrorKind::BrokenPipe
+        {
+            return Err(err.into());
+        }
+        if !output.status.success() {
+            return Err(FilterError::Exit(
+                output.status.code(),
+                String::from_utf8_lossy(&output.stderr).trim_end().to_owned(),
+            ));
+        }
+        String::from_utf8(output.stdout).ok().ok_or(FilterError::InvalidUtf8)
+    }
+}
+
+/// Errors that may occur when filtering lines with `!`.
+///
+/// The last error can be read with [`crate::Buffer::take_filter_error`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FilterError {
+    /// The command exited with a failure status, with its error output.
+    ///
+    /// The status is `None` if the command was killed by a signal.
+    Exit(Option<i32>, String),
+    /// The output of the command isn't valid UTF-8.
+    InvalidUtf8,
+    /// Failed to run the command.
+    Io(io::Error),
+    /// Error reported by a filter closure.
+    Message(String),
+    /// No filter was installed with [`crate::Buffer::set_filter`].
+    NoFilter,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exit(Some(code), stderr) =>
+                write!(f, "command exited with status {code}: {stderr}"),
+            Self::Exit(None, stderr) =>
+                write!(f, "command was killed: {stderr}"),
+            Self::InvalidUtf8 => "command output is not valid UTF-8".fmt(f),
+            Self::Io(err) => write!(f, "failed to run command: {err}"),
+            Self::Message(message) => message.fmt(f),
+            Self::NoFilter => "no filter installed".fmt(f),
+        }
+    }
+}
+
+impl Error for FilterError {}
+
+impl From<io::Error> for FilterError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -6,17 +6,36 @@ use crate::utils::bounded_usize::BoundedUsize;
 pub struct History<T>(Vec<T>, BoundedUsize);
 
 impl<T> History<T> {
+    /// Returns the position of the cursor in the history.
+    pub const fn as_cursor(&self) -> usize {
+        self.1.as_value()
+    }
+
     /// Returns the entry at the current cursor.
     #[expect(clippy::indexing_slicing, reason = "usize bounded by len")]
-    fn as_cursor_entry(&self) -> &T {
+    pub fn as_cursor_entry(&self) -> &T {
         &self.0[self.1.as_value()]
     }
 
-    #[cfg(test)]
+    /// Returns all the entries of the history, from oldest to newest.
     pub fn as_vec(&self) -> &[T] {
         &self.0
     }
 
+    /// Creates a new [`History`] from its entries and the position of the
+    /// cursor.
+    ///
+    /// Returns `None` if there are no entries or if the cursor is out of
+    /// bounds.
+    pub fn from_parts(entries: Vec<T>, cursor: usize) -> Option<Self> {
+        (cursor < entries.len()).then(|| {
+            let mut bounded =
+                BoundedUsize::with_capacity(entries.len().saturating_sub(1));
+            bounded.set(cursor);
+            Self(entries, bounded)
+        })
+    }
+
     /// Returns `true` if the cursor is at the end of the history.
     ///
     /// This means that you can't redo.
@@ -55,10 +74,15 @@ impl<T> History<T> {
 
 impl History<Box<str>> {
     /// Saves the current buffervalue in the history
-    pub fn save(&mut self, entry: &str) {
-        if *entry != **self.as_cursor_entry() {
+    ///
+    /// Returns `true` if the value was different from the last entry, and was
+    /// thus saved.
+    pub fn save(&mut self, entry: &str) -> bool {
+        let changed = *entry != **self.as_cursor_entry();
+        if changed {
             self.save_clone(Box::from(entry));
         }
+        changed
     }
 }
 
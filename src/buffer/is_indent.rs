@@ -45,7 +45,7 @@ pub enum IdentCharSpaceOrSymbol {
 
 impl From<char> for IdentCharSpaceOrSymbol {
     fn from(value: char) -> Self {
-        if matches!(value, '0'..='9' | 'a'..='z' | 'A'..='Z' | '_') {
+        if value.is_alphanumeric() || value == '_' {
             Self::IdentChar
         } else if value.is_whitespace() {
             Self::Space
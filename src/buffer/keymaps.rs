@@ -1,13 +1,20 @@
 use crate::Mode;
+use crate::buffer::mode::{Expression, Tag};
 
 /// Defines functions
 macro_rules! operator_impl {
     ($($t:tt: $c:tt,)*) => {
         /// Char that represents this operator. It is the char needed to apply
         /// the operator to the whole line.
-        pub(super) const fn as_char(self) -> char {
+        ///
+        /// Custom operators have no such char, as their keys are only known by
+        /// the buffer.
+        pub(super) const fn as_char(self) -> Option<char> {
             match self {
-                $(Self::$t => $c),*
+                $(Self::$t => Some($c),)*
+                // Only reached with `g`, as `c` is already `Self::Change`.
+                Self::Comment => Some('c'),
+                Self::Custom(_) => None,
             }
         }
 
@@ -24,22 +31,51 @@ macro_rules! operator_impl {
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Action {
+    /// Deletes the char before the cursor in insert mode, like `<BS>`
+    Backspace,
+    /// Forgets the region marked by a first `cx`, with `cxc`
+    CancelExchange,
+    /// Leaves the expression mode without evaluating the expression
+    CancelExpression,
+    /// Moves the cursor to an older position of the change list if `true`,
+    /// or a newer one otherwise, like `g;` and `g,`
+    ChangeList(bool),
     /// Clears the undo history for replace mode
     ClearUndoReplace,
+    /// Evaluates the expression being typed, and stores its result in the `=`
+    /// register
+    EvaluateExpression,
+    /// Removes the last char of the expression being typed
+    ExpressionBackspace,
+    /// Adds a char to the expression being typed
+    ExpressionChar(char),
+    /// Types and runs the command of a filter, like `!!sort<CR>`
+    Filter(FilterAction),
     /// Moves the cursor
     GoTo(GoToAction),
     /// Inserts a char at the current cursor
     InsertChar(char),
+    /// Inserts the content of a register at the current cursor, like `<C-r>`
+    InsertRegister(char),
+    /// Joins a number of lines, starting with the current one, like `J`
+    Join(Join, usize),
+    /// Moves the cursor to an older position of the jump list if `true`, or
+    /// a newer one otherwise, like `<C-o>` and `<C-i>`
+    JumpList(bool),
+    /// Opens a number of lines below the current one if `true`, or above it
+    /// otherwise, and starts insert mode, like `o` and `O`
+    ///
+    /// The text typed on the first line is repeated on the other ones when
+    /// leaving insert mode.
+    OpenLine(bool, usize),
     /// Applies an operator motion
     Operator(Operator, OperatorScope, usize),
-    /// Pastes the content of the clipboard after the cursor
-    PasteAfter,
-    /// Pastes the content of the clipboard before the cursor
-    PasteBefore,
+    /// Pastes the content of a register a number of times
+    Paste(Paste, usize),
     /// Undoes the last undo action
     Redo,
-    /// Repeats the last action
-    Repeat,
+    /// Repeats the last action, with a new count if any, like `.`
+    Repeat(Option<usize>),
     /// Inserts the char if the cursor is at the end of the buffer, otherwise
     /// replace the current char with the given one.
     ReplaceOrInsert(char),
@@ -47,6 +83,16 @@ pub enum Action {
     ReplaceWith(char),
     /// Switches to a new mode
     SelectMode(Mode),
+    /// Indents the current line by one level if `true`, or dedents it if
+    /// `false`, like `<C-t>` and `<C-d>` in insert mode
+    ShiftLine(bool),
+    /// Starts typing an expression for the `=` register
+    StartExpression(Expression),
+    /// Switches to insert or replace mode, and types the text a number of
+    /// times when leaving it, like `3i` and `3R`
+    StartInsert(Mode, usize),
+    /// Adds, changes or deletes a surrounding, like vim-surround
+    Surround(SurroundAction),
     /// Undoes the last edition
     Undo,
     /// Undoes the last replace action from replace mode
@@ -76,6 +122,18 @@ impl From<(Operator, OperatorPendingScope, Delimitation)> for Action {
     }
 }
 
+impl From<FilterAction> for Action {
+    fn from(value: FilterAction) -> Self {
+        Self::Filter(value)
+    }
+}
+
+impl From<SurroundAction> for Action {
+    fn from(value: SurroundAction) -> Self {
+        Self::Surround(value)
+    }
+}
+
 impl From<GoToAction> for Action {
     fn from(value: GoToAction) -> Self {
         Self::GoTo(value)
@@ -99,6 +157,8 @@ pub enum GoToAction {
     BeginningOfWORD,
     /// Move to the beginning of the previous word
     BeginningOfWord,
+    /// Motion registered by the host, with its index in the mappings
+    Custom(usize),
     /// End of line, like with `$` and `A`
     EndOfLine,
     /// Move to the end of the previous WORD, reached with `gE`
@@ -111,6 +171,9 @@ pub enum GoToAction {
     EndWord,
     /// First non space character, like with `I` and `^`
     FirstNonSpace,
+    /// Where insert mode was last left, or the cursor if it never was, used
+    /// by `gi`
+    LastInsert,
     /// Move the cursor left by one character
     Left,
     /// Move the cursor right by one character, stopping at the last character
@@ -132,13 +195,42 @@ pub enum GoToAction {
     Right,
 }
 
+impl GoToAction {
+    /// Returns `true` if the motion is a jump, whose starting position is
+    /// remembered in the jump list, like `%`.
+    pub const fn is_jump(self) -> bool {
+        matches!(self, Self::NextGroup)
+    }
+}
+
+/// Actions of the filter prompt, opened by `!{motion}`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Removes the last char of the command being typed
+    Backspace,
+    /// Leaves the filter prompt without filtering anything
+    Cancel,
+    /// Adds a char to the command being typed
+    Char(char),
+    /// Replaces the lines of an operator scope by the output of the typed
+    /// command, or of the last command when repeated with `.`
+    Run(OperatorScope, usize),
+}
+
 /// Action that is pending for another keypress
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum OPending {
+    /// Bracket command, like `]p`, waiting for its second char
+    Bracket(char),
+    /// Change surround command `cs`, waiting for the surrounding to replace
+    /// and the new one
+    ChangeSurround(Option<Surrounding>),
     /// Pending action that only requires 1 character to form a goto action.
     ///
     /// Combinable with an [`Operator`], see [`Self::OperatorAction`].
     CombinablePending(CombinablePending),
+    /// Delete surround command `ds`, waiting for the surrounding to delete
+    DeleteSurround,
     /// Applies a single char action to a motion.
     GoTo,
     /// Operator action, like `d`, `c`, `g~`
@@ -149,6 +241,8 @@ pub enum OPending {
     OperatorAction(Operator, CombinablePending),
     /// Replace one character
     ReplaceOne,
+    /// Surround command `ys{motion}`, waiting for the surrounding to add
+    Surround(OperatorScope, usize),
 }
 
 impl OPending {
@@ -158,10 +252,14 @@ impl OPending {
     /// `None`.
     pub fn maybe_with_num(self, num: usize) -> Option<Self> {
         match self {
-            Self::CombinablePending(_)
+            Self::Bracket(_)
+            | Self::ChangeSurround(_)
+            | Self::CombinablePending(_)
+            | Self::DeleteSurround
             | Self::GoTo
             | Self::OperatorAction(..)
             | Self::ReplaceOne
+            | Self::Surround(..)
             | Self::Operator(_, Some(_), _) => None,
             Self::Operator(op, None, old) => Some(Self::Operator(
                 op,
@@ -172,6 +270,44 @@ impl OPending {
     }
 }
 
+/// Variant of the join command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Join {
+    /// Joins the lines without changing their whitespace, with `gJ`
+    Raw,
+    /// Removes the indent of the joined lines and separates them with a
+    /// space, with `J`
+    Spaces,
+}
+
+/// Variant of the paste command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paste {
+    /// Pastes after the cursor, with `p`
+    After,
+    /// Pastes after the cursor, with the indent of the current line, with
+    /// `]p`
+    AfterIndent,
+    /// Pastes after the cursor, and leaves the cursor just after the pasted
+    /// text, with `gp`
+    AfterSkip,
+    /// Pastes before the cursor, with `P`
+    Before,
+    /// Pastes before the cursor, with the indent of the current line, with
+    /// `[p`
+    BeforeIndent,
+    /// Pastes before the cursor, and leaves the cursor just after the pasted
+    /// text, with `gP`
+    BeforeSkip,
+}
+
+impl Paste {
+    /// Returns `true` if the text is pasted after the cursor.
+    pub const fn is_after(self) -> bool {
+        matches!(self, Self::After | Self::AfterIndent | Self::AfterSkip)
+    }
+}
+
 /// Pending Scope of an operator, like inner `i` or around `a`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum OperatorPendingScope {
@@ -214,10 +350,46 @@ pub enum Operator {
     Capitalise,
     /// Change content of motion
     Change,
+    /// Comments the lines of motion, or uncomments them if they are all
+    /// commented, like `gc`
+    Comment,
+    /// Operator registered by the host, with its index in the mappings
+    Custom(usize),
+    /// Removes one indent level from the lines of motion, like `<<`
+    Dedent,
     /// Delete content of motion
     Delete,
+    /// Marks the content of motion, and swaps it with the previously marked
+    /// one, like vim-exchange's `cx`
+    Exchange,
+    /// Replaces the lines of motion by the output of a command, like `!!`
+    ///
+    /// The command is typed after the motion: this only opens the prompt
+    /// for it, see [`FilterAction::Run`].
+    Filter,
+    /// Wraps the lines of motion to `textwidth`, like `gq`
+    Format,
+    /// Wraps the lines of motion to `textwidth` without moving the cursor,
+    /// like `gw`
+    FormatKeepCursor,
+    /// Adds one indent level to the lines of motion, like `>>`
+    Indent,
     /// Lowers the case of content of motion
     LowerCase,
+    /// Replaces the content of motion with a register, without changing the
+    /// unnamed register, like the `gr` of `ReplaceWithRegister`
+    ReplaceWithRegister,
+    /// Rotates the ASCII letters of content of motion by 13 letters, like
+    /// `g?`
+    Rot13,
+    /// Surrounds the content of motion, like vim-surround's `ys`
+    ///
+    /// Only used while parsing the keymaps, as the surrounding is typed after
+    /// the motion: see [`SurroundAction::Add`].
+    Surround,
+    /// Capitalises the first letter of each word of content of motion, and
+    /// lowers the other ones, like `gz`
+    TitleCase,
     /// Lowers capitals and capitalises lower case letters
     ToggleCase,
     /// Copies the content of motion in clipboard
@@ -228,8 +400,18 @@ impl Operator {
     operator_impl! {
         Capitalise: 'U',
         Change: 'c',
+        Dedent: '<',
         Delete: 'd',
+        Exchange: 'x',
+        Filter: '!',
+        Format: 'q',
+        FormatKeepCursor: 'w',
+        Indent: '>',
         LowerCase: 'u',
+        ReplaceWithRegister: 'r',
+        Rot13: '?',
+        Surround: 's',
+        TitleCase: 'z',
         ToggleCase: '~',
         Yank: 'y',
     }
@@ -242,6 +424,9 @@ impl Operator {
 pub enum OperatorScope {
     /// Apply operator on the around of an operation (e.g., `aw`)
     Around(Delimitation, usize),
+    /// Apply operator on a text object registered by the host, with its
+    /// index in the mappings
+    Custom(usize),
     /// Apply the operator on simply those actions
     Goto(GoToAction, Option<GoToAction>),
     /// Apply operator on the inner of an operation (e.g., `iw`)
@@ -256,6 +441,11 @@ pub enum OperatorScope {
 pub enum Delimitation {
     /// Between a `{,[,v,(` group
     Group(char, char),
+    /// Represents a paragraph, made of lines that are all blank or all
+    /// non-blank
+    Paragraph,
+    /// Between two quotes of the current line, like `"`
+    Quote(char),
     /// Represents a vim WORD
     WORD,
     /// Represents a vim word
@@ -270,6 +460,8 @@ impl Delimitation {
             '[' | ']' => Self::Group('[', ']'),
             '{' | '}' => Self::Group('{', '}'),
             '<' | '>' => Self::Group('<', '>'),
+            '"' | '\'' | '`' => Self::Quote(value),
+            'p' => Self::Paragraph,
             'W' => Self::WORD,
             'w' => Self::Word,
             _ => return None,
@@ -277,12 +469,106 @@ impl Delimitation {
     }
 }
 
+/// Actions of the surround commands and of their tag prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurroundAction {
+    /// Surrounds the text of an operator scope, like `ysiw)`
+    Add(OperatorScope, usize, Surrounding),
+    /// Leaves the tag prompt without surrounding anything
+    CancelTag,
+    /// Replaces the surrounding around the cursor by another one, like
+    /// `cs"'`
+    Change(Surrounding, Surrounding),
+    /// Removes the surrounding around the cursor, like `ds(`
+    Delete(Surrounding),
+    /// Leaves the tag prompt, and keeps the typed tag for the surround
+    /// actions
+    EndTag,
+    /// Starts typing a tag for the surround commands, after `<` or `t`
+    StartTag(Tag),
+    /// Removes the last char of the tag being typed
+    TagBackspace,
+    /// Adds a char to the tag being typed
+    TagChar(char),
+}
+
+/// Text added around some other text by the surround commands, like `ys`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Surrounding {
+    /// Pair of chars, like `(` and `)`, with inner spaces if `true`
+    Pair(char, char, bool),
+    /// Last tag typed in the tag prompt, like `<em>` and `</em>`
+    Tag,
+}
+
+impl Surrounding {
+    /// Tries to return the [`Surrounding`] to add for this char, as in
+    /// `ysiw)`
+    ///
+    /// Opening brackets add inner spaces, and `<` or `t` prompt for a tag.
+    /// Other punctuation chars are used on both sides.
+    pub const fn maybe_from(ch: char) -> Option<Self> {
+        Some(match ch {
+            '(' => Self::Pair('(', ')', true),
+            ')' | 'b' => Self::Pair('(', ')', false),
+            '[' => Self::Pair('[', ']', true),
+            ']' | 'r' => Self::Pair('[', ']', false),
+            '{' => Self::Pair('{', '}', true),
+            '}' | 'B' => Self::Pair('{', '}', false),
+            '>' | 'a' => Self::Pair('<', '>', false),
+            '<' | 't' => Self::Tag,
+            _ if ch.is_ascii_punctuation() => Self::Pair(ch, ch, false),
+            _ => return None,
+        })
+    }
+
+    /// Tries to return the [`Surrounding`] to find around the cursor for
+    /// this char, as in `ds(`
+    ///
+    /// Opening brackets also match the inner spaces. Tags can't be found.
+    pub const fn maybe_from_target(ch: char) -> Option<Self> {
+        match ch {
+            '<' => Some(Self::Pair('<', '>', false)),
+            _ => match Self::maybe_from(ch) {
+                Some(Self::Tag) | None => None,
+                pair @ Some(Self::Pair(..)) => pair,
+            },
+        }
+    }
+}
+
+impl OperatorScope {
+    /// Returns `true` if the operator applies on whole lines, like `dd` and
+    /// `dip`.
+    pub const fn is_linewise(self) -> bool {
+        matches!(
+            self,
+            Self::WholeLine
+                | Self::Around(Delimitation::Paragraph, _)
+                | Self::Inner(Delimitation::Paragraph, _)
+        )
+    }
+}
+
 impl From<GoToAction> for OperatorScope {
     fn from(value: GoToAction) -> Self {
         Self::Goto(value, None)
     }
 }
 
+/// Keymap registered by the host, once all its keys are typed
+///
+/// It holds the index of the keymap in the mappings of the buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CustomKeymap {
+    /// Custom motion, like a built-in `w`
+    Motion(usize),
+    /// Custom text object, like a built-in `iw`
+    Object(usize),
+    /// Custom operator, like a built-in `d`
+    Operator(usize),
+}
+
 /// Pending action that only requires 1 character to form a goto action.
 ///
 /// Can be combined with an [`Operator`] (change, delete, toggle case, etc.),
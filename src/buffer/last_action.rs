@@ -1,27 +1,60 @@
-use crate::buffer::keymaps::Action;
+use crate::buffer::keymaps::{
+    Action, FilterAction, GoToAction, Operator, SurroundAction
+};
+use crate::buffer::mode::scale;
 use crate::{Buffer, Mode};
 
 /// Last action done on the buffer, used by the `.` keymap
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct LastAction {
-    /// List of actions to be performed
-    actions: Vec<Action>,
+    /// Actions of the command, as typed without a count
+    command: Vec<Action>,
+    /// Count the command was typed with, applied with [`scale`]
+    count: Option<usize>,
     /// On what buffer mode they should be performed
     mode: Mode,
     /// Register used for that action
     reg: Option<char>,
+    /// Actions typed in insert or replace mode after the command
+    typed: Vec<Action>,
 }
 
 impl LastAction {
-    /// Performs the last action on the given buffer.
-    pub fn perform(&self, buffer: &mut Buffer) -> bool {
+    /// Performs the last action on the given buffer, like `.`
+    ///
+    /// A count given to `.` replaces the count the command was typed with,
+    /// and is kept for the next repetitions.
+    ///
+    /// As with `<Esc>`, the cursor is moved left after repeating an insertion
+    /// from normal mode.
+    ///
+    /// When repeating a paste from a numbered register, the next numbered
+    /// register is used, so that `"1p..` pastes `"1`, then `"2` and `"3`.
+    pub fn perform(
+        &mut self,
+        buffer: &mut Buffer,
+        count: Option<usize>,
+    ) -> bool {
+        if let Some(new_count) = count {
+            self.count = Some(new_count);
+        }
+        if let Some(reg @ '1'..='8') = self.reg
+            && self
+                .command
+                .iter()
+                .all(|action| matches!(action, Action::Paste(..)))
+        {
+            self.reg = char::from_u32(u32::from(reg).saturating_add(1));
+        }
         let old_mode = buffer.as_mode();
         if buffer.update_once(self.mode.into(), self.reg)
-            && self
-                .actions
+            && scale(self.command.clone(), self.count)
                 .iter()
+                .chain(&self.typed)
                 .all(|action| buffer.update_once(*action, self.reg))
             && buffer.update_once(old_mode.into(), self.reg)
+            && (self.mode == old_mode
+                || buffer.update_once(GoToAction::Left.into(), self.reg))
         {
             buffer.save_to_history();
             true
@@ -31,24 +64,71 @@ impl LastAction {
     }
 
     /// Updates the [`LastAction`] with a list of actions.
-    pub fn update(&mut self, actions: Vec<Action>, mode: Mode) {
-        if actions == [Action::Repeat]
-            || actions.iter().all(|action| {
-                matches!(
-                    action,
-                    Action::GoTo(_)
-                        | Action::SelectMode(_)
-                        | Action::ClearUndoReplace
-                )
-            })
-        {
+    ///
+    /// The actions typed in insert or replace mode are added after the
+    /// command that entered it, until another command enters it again.
+    pub fn update(
+        &mut self,
+        actions: Vec<Action>,
+        count: Option<usize>,
+        mode: Mode,
+        reg: Option<char>,
+    ) {
+        if is_ignored(&actions) {
             return;
         }
-        if mode != Mode::Normal && self.mode == mode {
-            self.actions.extend(actions);
+        let starts_typing = actions.iter().any(|action| {
+            matches!(
+                action,
+                Action::OpenLine(..)
+                    | Action::SelectMode(Mode::Insert | Mode::Replace)
+                    | Action::StartInsert(..)
+            )
+        });
+        if !starts_typing && mode != Mode::Normal && self.mode == mode {
+            self.typed.extend(scale(actions, count));
         } else {
-            self.actions = actions;
+            self.command = actions;
+            self.count = count;
+            self.reg = reg;
+            self.typed.clear();
         }
         self.mode = mode;
     }
 }
+
+/// Returns `true` if the actions don't edit the buffer, and are thus not
+/// repeated by `.`, like motions, undo and `.` itself.
+fn is_ignored(actions: &[Action]) -> bool {
+    matches!(*actions, [Action::Repeat(_)])
+        || actions.iter().all(|action| {
+            matches!(
+                action,
+                Action::GoTo(_)
+                    | Action::SelectMode(_)
+                    | Action::ClearUndoReplace
+                    | Action::CancelExchange
+                    | Action::CancelExpression
+                    | Action::ChangeList(_)
+                    | Action::JumpList(_)
+                    | Action::Surround(
+                        SurroundAction::CancelTag
+                            | SurroundAction::StartTag(_)
+                            | SurroundAction::TagBackspace
+                            | SurroundAction::TagChar(_)
+                    )
+                    | Action::Filter(
+                        FilterAction::Backspace
+                            | FilterAction::Cancel
+                            | FilterAction::Char(_)
+                    )
+                    | Action::Operator(Operator::Filter, ..)
+                    | Action::EvaluateExpression
+                    | Action::ExpressionBackspace
+                    | Action::ExpressionChar(_)
+                    | Action::StartExpression(_)
+                    | Action::Undo
+                    | Action::Redo
+            )
+        })
+}
@@ -0,0 +1,168 @@
+use core::{fmt, slice};
+
+use crossterm::event::{Event, KeyEvent};
+
+use crate::buffer::keymaps::{CustomKeymap, Operator};
+use crate::buffer::mode::fix_shift_modifier;
+use crate::event_parser::{EventParsingError, parse_events};
+
+/// Custom motion, that returns the new cursor from the content and the
+/// cursor
+pub type MotionFn = dyn FnMut(&str, usize) -> Option<usize> + Send;
+/// Custom text object, that returns the start and end of the object from the
+/// content and the cursor
+pub type ObjectFn = dyn FnMut(&str, usize) -> Option<(usize, usize)> + Send;
+/// Custom operator, that returns the replacement of the text of the motion
+pub type OperatorFn = dyn FnMut(&str) -> Option<String> + Send;
+
+/// Keymap a mapping can be typed as, with the keys to type
+type Candidate<'keys> = Option<(CustomKeymap, &'keys [KeyEvent])>;
+
+/// Function called by a mapping
+pub enum MappingFn {
+    /// Moves the cursor, see [`crate::Buffer::map_motion`]
+    Motion(Box<MotionFn>),
+    /// Selects a text object, see [`crate::Buffer::map_text_object`]
+    Object(Box<ObjectFn>),
+    /// Replaces the text of a motion, see [`crate::Buffer::map_operator`]
+    Operator(Box<OperatorFn>),
+}
+
+/// Keys bound by the host to a function
+struct Mapping {
+    /// Function called once the keys are typed
+    function: MappingFn,
+    /// Keys to type, normalised with [`fix_shift_modifier`]
+    keys: Vec<KeyEvent>,
+}
+
+impl Mapping {
+    /// Returns the keymaps this mapping can be typed as, with the keys to
+    /// type, when `operator` is waiting for its motion.
+    ///
+    /// Text objects can only be typed after an operator, and operators
+    /// can't, except for the mapped operator itself, to apply it on whole
+    /// lines like `gcc`.
+    fn candidates(
+        &self,
+        id: usize,
+        operator: Option<Operator>,
+    ) -> [Candidate<'_>; 2] {
+        let keys = self.keys.as_slice();
+        match (&self.function, operator) {
+            (MappingFn::Motion(_), _) =>
+                [Some((CustomKeymap::Motion(id), keys)), None],
+            (MappingFn::Object(_), Some(_)) =>
+                [Some((CustomKeymap::Object(id), keys)), None],
+            (MappingFn::Operator(_), None) =>
+                [Some((CustomKeymap::Operator(id), keys)), None],
+            (MappingFn::Operator(_), Some(op))
+                if op == Operator::Custom(id) =>
+                [
+                    Some((CustomKeymap::Operator(id), keys)),
+                    keys.last().map(|last| {
+                        (CustomKeymap::Operator(id), slice::from_ref(last))
+                    }),
+                ],
+            (MappingFn::Object(_) | MappingFn::Operator(_), _) => [None, None],
+        }
+    }
+}
+
+/// Result of the search of the typed keys in the mappings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMatch {
+    /// The keys are exactly those of a mapping
+    Full(CustomKeymap),
+    /// The keys don't start any mapping
+    None,
+    /// The keys start a mapping, and more keys are needed
+    Prefix,
+}
+
+/// Operators, motions and text objects registered by the host
+#[derive(Default)]
+pub struct Mappings {
+    /// Mappings, indexed by the [`CustomKeymap`]s
+    mappings: Vec<Mapping>,
+}
+
+impl Mappings {
+    /// Returns the keymap typed with `keys`, when `operator` is waiting for
+    /// its motion.
+    ///
+    /// The first mapping that matches wins, even if another one starts with
+    /// the same keys.
+    pub fn find(
+        &self,
+        keys: &[KeyEvent],
+        operator: Option<Operator>,
+    ) -> KeyMatch {
+        let candidates = self
+            .mappings
+            .iter()
+            .enumerate()
+            .flat_map(|(id, mapping)| mapping.candidates(id, operator))
+            .flatten();
+        let mut is_prefix = false;
+        for (keymap, sequence) in candidates {
+            if sequence == keys {
+                return KeyMatch::Full(keymap);
+            }
+            is_prefix |= sequence.starts_with(keys);
+        }
+        if is_prefix { KeyMatch::Prefix } else { KeyMatch::None }
+    }
+
+    /// Returns the function of a mapping.
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut MappingFn> {
+        self.mappings.get_mut(id).map(|mapping| &mut mapping.function)
+    }
+
+    /// Binds the keys of `keymaps` to a function, replacing the mapping of the
+    /// same keys, if any.
+    ///
+    /// Empty keys are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `keymaps` isn't a valid keymap string.
+    pub fn insert(
+        &mut self,
+        keymaps: &str,
+        function: MappingFn,
+    ) -> Result<(), EventParsingError> {
+        let keys = parse_events(keymaps)?
+            .iter()
+            .filter_map(as_key)
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        if let Some(mapping) =
+            self.mappings.iter_mut().find(|mapping| mapping.keys == keys)
+        {
+            mapping.function = function;
+        } else {
+            self.mappings.push(Mapping { function, keys });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Mappings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.mappings.iter().map(|mapping| &mapping.keys))
+            .finish()
+    }
+}
+
+/// Returns the key pressed by an event, to compare it with the keys of the
+/// mappings.
+pub fn as_key(event: &Event) -> Option<KeyEvent> {
+    event.as_key_press_event().map(|mut key| {
+        fix_shift_modifier(&mut key);
+        KeyEvent::new(key.code, key.modifiers)
+    })
+}
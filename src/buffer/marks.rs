@@ -0,0 +1,62 @@
+use crate::buffer::position_list::adjust_position;
+
+/// Marks set automatically by the buffer, like `'[` and `'^`
+#[derive(Debug, Default, Clone)]
+pub struct Marks {
+    /// Start and last char of the last changed or yanked text, for `'[` and
+    /// `']`
+    change: Option<(usize, usize)>,
+    /// Where insert mode was last left, for `'^`
+    insert: Option<usize>,
+    /// Where insert mode was left since the last change was saved
+    ///
+    /// It only becomes `'^` once that change is saved, as it already takes it
+    /// into account.
+    left_insert: Option<usize>,
+}
+
+impl Marks {
+    /// Updates the marks after the text between `start` and `old_end` was
+    /// replaced by text ending at `new_end`, with [`adjust_position`].
+    pub fn adjust(&mut self, start: usize, old_end: usize, new_end: usize) {
+        let adjust =
+            |position| adjust_position(position, start, old_end, new_end);
+        self.change =
+            self.change.map(|(first, last)| (adjust(first), adjust(last)));
+        self.insert = self.insert.map(adjust);
+    }
+
+    /// Returns the position of an automatic mark, among `'[`, `']` and `'^`.
+    pub fn get(&self, name: char) -> Option<usize> {
+        match name {
+            '[' => self.change.map(|(first, _)| first),
+            ']' => self.change.map(|(_, last)| last),
+            '^' => self.left_insert.or(self.insert),
+            _ => None,
+        }
+    }
+
+    /// Remembers where insert mode was left, for `'^`.
+    pub const fn leave_insert(&mut self, position: usize) {
+        self.left_insert = Some(position);
+    }
+
+    /// Makes the position where insert mode was left `'^`, once the change
+    /// made in insert mode was saved.
+    pub const fn save_insert(&mut self) {
+        if let Some(position) = self.left_insert.take() {
+            self.insert = Some(position);
+        }
+    }
+
+    /// Sets `'[` and `']` to the text of `content` between `start` and `end`.
+    ///
+    /// Both are set to `start` if the text is empty, like after a deletion.
+    pub fn set_change(&mut self, content: &str, start: usize, end: usize) {
+        let last = content
+            .get(start..end)
+            .and_then(|text| text.char_indices().next_back())
+            .map_or(start, |(idx, _)| start.saturating_add(idx));
+        self.change = Some((start, last));
+    }
+}
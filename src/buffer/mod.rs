@@ -1,5 +1,11 @@
 /// API to interact with the [`Buffer`]
 mod api;
+/// Access to the system clipboard for the `+` and `*` registers
+mod clipboard;
+/// Evaluates the Vimscript expressions of the `=` register
+mod eval;
+/// Filters that replace lines by the output of a command, for `!`
+mod filter;
 /// Logic to hold the history of the buffer
 mod history;
 /// Handles the checks to delimitate a vim word.
@@ -10,15 +16,34 @@ mod keymaps;
 mod last_action;
 /// Useful macros scoped with this module.
 mod macros;
+/// Operators, motions and text objects registered by the host
+mod mappings;
+/// Marks set automatically by the buffer, like `'[` and `'^`
+mod marks;
 /// Handles the vim modes and the keypresses on those modes
 mod mode;
+/// Vim options of the buffer, like `shiftwidth`
+mod options;
+/// Lists of positions to walk through, like the jump list and change list
+mod position_list;
+/// Stores that share the registers between buffers
+mod register_store;
 /// Handles the different vim registers.
 mod registers;
+/// Reads and writes the history to keep undo across sessions
+mod undo_file;
 /// Methods to update the [`Buffer`] with keymaps.
 mod update;
 
 pub use api::Buffer;
+pub use clipboard::{
+    ClipboardOption, ClipboardProvider, ClipboardSelection, CommandProvider, MemoryProvider
+};
+pub use filter::{CommandFilter, FilterError, FilterProvider};
 pub use mode::Mode;
+pub use register_store::SharedRegisters;
+pub use registers::{RegisterEntry, RegisterType};
+pub use undo_file::UndoFileError;
 
 #[cfg(test)]
 mod tests;
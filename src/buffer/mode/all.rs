@@ -1,8 +1,11 @@
 use crossterm::event::Event;
 
+use crate::buffer::mode::expression::Expression;
+use crate::buffer::mode::filter::Filter;
 use crate::buffer::mode::insert::Insert;
 use crate::buffer::mode::normal::Normal;
 use crate::buffer::mode::replace::Replace;
+use crate::buffer::mode::tag::Tag;
 use crate::buffer::mode::traits::{Actions, HandleKeyPress as _};
 
 /// Represents the vim mode of the buffer.
@@ -22,12 +25,18 @@ pub enum Mode {
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BufferMode {
+    /// Typing an expression for the `=` register
+    Expression(Expression),
+    /// Typing the command of a filter, after `!{motion}`
+    Filter(Filter),
     /// Insert mode
-    Insert,
+    Insert(Insert),
     /// Normal mode
     Normal(Normal),
     /// Replace mode
     Replace,
+    /// Typing a tag for the surround commands
+    Tag(Tag),
 }
 
 impl Default for BufferMode {
@@ -37,12 +46,31 @@ impl Default for BufferMode {
 }
 
 impl BufferMode {
+    /// Leaves the expression mode, to go back to the mode the expression was
+    /// started from.
+    ///
+    /// If `select_register` is `true` and the expression was started from
+    /// normal mode, the `=` register is selected for the next action.
+    pub const fn end_expression(&mut self, select_register: bool) {
+        if let Self::Expression(expression) = *self {
+            *self = match expression {
+                Expression::Insert => Self::Insert(Insert::None),
+                Expression::Normal(pre) if select_register =>
+                    Self::Normal(Normal::Register(pre, Some('='))),
+                Expression::Normal(_) => Self::Normal(Normal::new()),
+            };
+        }
+    }
+
     /// Handle incoming terminal events off any kind.
     pub fn handle_event(&mut self, event: Event) -> Actions {
         match self {
-            Self::Insert => Insert.handle_key(event),
+            Self::Expression(expression) => expression.handle_key(event),
+            Self::Filter(filter) => filter.handle_key(event),
+            Self::Insert(insert) => insert.handle_key(event),
             Self::Normal(normal) => normal.handle_key(event),
             Self::Replace => Replace.handle_key(event),
+            Self::Tag(tag) => tag.handle_key(event),
         }
     }
 
@@ -50,7 +78,7 @@ impl BufferMode {
     /// will be lost, even if the new mode is the same as the last one.
     pub const fn switch_to(&mut self, mode: Mode) {
         *self = match mode {
-            Mode::Insert => Self::Insert,
+            Mode::Insert => Self::Insert(Insert::None),
             Mode::Normal => Self::Normal(Normal::new()),
             Mode::Replace => Self::Replace,
         };
@@ -59,8 +87,9 @@ impl BufferMode {
     /// Returns the [`Mode`] that corresponds to the current [`BufferMode`].
     pub const fn to_mode(self) -> Mode {
         match self {
-            Self::Insert => Mode::Insert,
-            Self::Normal(_) => Mode::Normal,
+            Self::Expression(expression) => expression.to_mode(),
+            Self::Insert(_) => Mode::Insert,
+            Self::Filter(_) | Self::Normal(_) | Self::Tag(_) => Mode::Normal,
             Self::Replace => Mode::Replace,
         }
     }
@@ -0,0 +1,59 @@
+use crossterm::event::KeyCode;
+
+use crate::buffer::keymaps::Action;
+use crate::buffer::macros::actions;
+use crate::buffer::mode::all::Mode;
+use crate::buffer::mode::traits::{Actions, HandleKeyPress};
+
+/// Struct to handle keypresses while typing an expression for the `=`
+/// register, after `"=` or `<C-r>=`.
+///
+/// It holds the mode the expression was started from, to go back to it once
+/// the expression is evaluated.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Expression {
+    /// Started with `<C-r>=` in insert mode: the result is inserted
+    Insert,
+    /// Started with `"=` in normal mode, after an optional count: the `=`
+    /// register is then selected for the next action, like `p`
+    Normal(Option<usize>),
+}
+
+impl Expression {
+    /// Returns the [`Mode`] the expression was started from.
+    pub const fn to_mode(self) -> Mode {
+        match self {
+            Self::Insert => Mode::Insert,
+            Self::Normal(_) => Mode::Normal,
+        }
+    }
+}
+
+#[expect(clippy::wildcard_enum_match_arm, reason = "only support a few")]
+impl HandleKeyPress for Expression {
+    fn handle_blank_key_press(&mut self, code: KeyCode) -> Actions {
+        match code {
+            KeyCode::Enter if *self == Self::Insert => actions![
+                Action::EvaluateExpression,
+                Action::InsertRegister('=')
+            ],
+            KeyCode::Enter => Action::EvaluateExpression.into(),
+            KeyCode::Esc => Action::CancelExpression.into(),
+            KeyCode::Backspace => Action::ExpressionBackspace.into(),
+            KeyCode::Char(ch) => Action::ExpressionChar(ch).into(),
+            _ => Actions::Unsupported,
+        }
+    }
+
+    fn handle_ctrl_key_press(&mut self, _: KeyCode) -> Actions {
+        Actions::Unsupported
+    }
+
+    fn handle_shift_key_press(&mut self, code: KeyCode) -> Actions {
+        if let KeyCode::Char(ch) = code {
+            Action::ExpressionChar(ch.to_ascii_uppercase()).into()
+        } else {
+            Actions::Unsupported
+        }
+    }
+}
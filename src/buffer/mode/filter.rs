@@ -0,0 +1,48 @@
+use crossterm::event::KeyCode;
+
+use crate::buffer::keymaps::{FilterAction, OperatorScope};
+use crate::buffer::mode::traits::{Actions, HandleKeyPress};
+
+/// Struct to handle keypresses while typing the command of a filter, after
+/// `!{motion}` or `!!`.
+///
+/// It holds the motion of the filter, to apply it once the command is typed.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Filter {
+    /// Number of times the motion is applied
+    count: usize,
+    /// Motion that gives the lines to filter
+    scope: OperatorScope,
+}
+
+impl Filter {
+    /// Returns a prompt for the command that filters the lines of `scope`.
+    pub const fn new(scope: OperatorScope, count: usize) -> Self {
+        Self { count, scope }
+    }
+}
+
+#[expect(clippy::wildcard_enum_match_arm, reason = "only support a few")]
+impl HandleKeyPress for Filter {
+    fn handle_blank_key_press(&mut self, code: KeyCode) -> Actions {
+        match code {
+            KeyCode::Enter => FilterAction::Run(self.scope, self.count).into(),
+            KeyCode::Esc => FilterAction::Cancel.into(),
+            KeyCode::Backspace => FilterAction::Backspace.into(),
+            KeyCode::Char(ch) => FilterAction::Char(ch).into(),
+            _ => Actions::Unsupported,
+        }
+    }
+
+    fn handle_ctrl_key_press(&mut self, _: KeyCode) -> Actions {
+        Actions::Unsupported
+    }
+
+    fn handle_shift_key_press(&mut self, code: KeyCode) -> Actions {
+        if let KeyCode::Char(ch) = code {
+            FilterAction::Char(ch.to_ascii_uppercase()).into()
+        } else {
+            Actions::Unsupported
+        }
+    }
+}
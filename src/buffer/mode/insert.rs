@@ -1,12 +1,20 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{Event, KeyCode};
 
-use crate::buffer::keymaps::{Action, GoToAction, Operator};
+use crate::buffer::keymaps::{Action, GoToAction};
 use crate::buffer::macros::actions;
 use crate::buffer::mode::all::Mode;
+use crate::buffer::mode::expression::Expression;
 use crate::buffer::mode::traits::{Actions, HandleKeyPress};
 
 /// Struct to handle keypresses in insert mode
-pub struct Insert;
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy)]
+pub enum Insert {
+    /// Insert mode but no keypress is pending
+    #[default]
+    None,
+    /// `<C-r>` was pressed, and the name of a register is pending
+    Register,
+}
 
 #[expect(
     clippy::wildcard_enum_match_arm,
@@ -15,20 +23,44 @@ pub struct Insert;
 impl HandleKeyPress for Insert {
     fn handle_blank_key_press(&mut self, code: KeyCode) -> Actions {
         match code {
-            KeyCode::Esc => actions![GoToAction::Left, Mode::Normal],
+            KeyCode::Esc => actions![Mode::Normal, GoToAction::Left],
             KeyCode::Char(ch) => Action::InsertChar(ch).into(),
-            KeyCode::Backspace => actions![
-                GoToAction::Left,
-                (Operator::Delete, GoToAction::Right.into())
-            ],
+            KeyCode::Enter => Action::InsertChar('\n').into(),
+            KeyCode::Backspace => Action::Backspace.into(),
             KeyCode::Left => GoToAction::Left.into(),
             KeyCode::Right => GoToAction::Right.into(),
             _ => Actions::Unsupported,
         }
     }
 
-    fn handle_ctrl_key_press(&mut self, _: KeyCode) -> Actions {
-        Actions::Unsupported
+    fn handle_ctrl_key_press(&mut self, code: KeyCode) -> Actions {
+        match code {
+            KeyCode::Char('a') => Action::InsertRegister('.').into(),
+            KeyCode::Char('d') => Action::ShiftLine(false).into(),
+            KeyCode::Char('r') => {
+                *self = Self::Register;
+                Actions::None
+            }
+            KeyCode::Char('t') => Action::ShiftLine(true).into(),
+            _ => Actions::Unsupported,
+        }
+    }
+
+    fn handle_key(&mut self, event: Event) -> Actions {
+        match *self {
+            Self::None => self.default_handle_key(event),
+            Self::Register => {
+                *self = Self::None;
+                match event.as_key_press_event().map(|ev| ev.code) {
+                    Some(KeyCode::Char('=')) =>
+                        Action::StartExpression(Expression::Insert).into(),
+                    Some(KeyCode::Char(reg)) =>
+                        Action::InsertRegister(reg).into(),
+                    Some(KeyCode::Esc) => Actions::None,
+                    _ => Actions::Unsupported,
+                }
+            }
+        }
     }
 
     fn handle_shift_key_press(&mut self, code: KeyCode) -> Actions {
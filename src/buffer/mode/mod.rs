@@ -1,16 +1,25 @@
 /// Defines the types for the different modes.
 mod all;
+/// Handles keypresses while typing an expression for the `=` register
+mod expression;
+/// Handles keypresses while typing the command of a filter
+mod filter;
 /// Handles keypresses in insert mode
 mod insert;
 /// Handles keypresses in normal mode
 mod normal;
 /// Handles keypresses in replace mode
 mod replace;
+/// Handles keypresses while typing a tag for the surround commands
+mod tag;
 /// Defines the types and traits to organise modes and how the process events.
 mod traits;
 
 pub use all::{BufferMode, Mode};
-pub use traits::Actions;
+pub use expression::Expression;
+pub use filter::Filter;
+pub use tag::Tag;
+pub use traits::{Actions, fix_shift_modifier, scale};
 
 #[cfg(test)]
 mod tests;
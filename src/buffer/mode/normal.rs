@@ -1,10 +1,12 @@
 use crossterm::event::{Event, KeyCode};
 
 use crate::buffer::keymaps::{
-    Action, CombinablePending, Delimitation, GoToAction, OPending, Operator, OperatorPendingScope, OperatorScope
+    Action, CombinablePending, CustomKeymap, Delimitation, GoToAction, Join, OPending, Operator, OperatorPendingScope, OperatorScope, Paste, SurroundAction, Surrounding
 };
 use crate::buffer::macros::actions;
 use crate::buffer::mode::all::Mode;
+use crate::buffer::mode::expression::Expression;
+use crate::buffer::mode::tag::Tag;
 use crate::buffer::mode::traits::{Actions, HandleKeyPress};
 
 /// Struct to handle keypresses in normal mode
@@ -25,6 +27,30 @@ pub enum Normal {
 }
 
 impl Normal {
+    /// Returns `true` if the next keys may be those of a keymap registered by
+    /// the host, that is if no key is pending, except for counts, a register
+    /// or an operator waiting for its motion.
+    pub const fn accepts_custom(self) -> bool {
+        matches!(
+            self,
+            Self::None
+                | Self::PreNum(_)
+                | Self::Register(_, Some(_))
+                | Self::MidNum(..)
+                | Self::Pending(.., OPending::Operator(_, None, _))
+        )
+    }
+
+    /// Handles a keymap registered by the host, once all its keys are typed.
+    pub fn handle_custom(&mut self, keymap: CustomKeymap) -> Actions {
+        let actions =
+            self.with_counts(|normal| normal.handle_custom_once(keymap));
+        if actions != Actions::None {
+            *self = Self::None;
+        }
+        actions
+    }
+
     /// Returns a default [`Normal`]
     pub const fn new() -> Self {
         Self::None
@@ -73,9 +99,74 @@ impl Normal {
         };
         Actions::None
     }
+
+    /// Waits for the surrounding once the motion of `ys` is known, instead of
+    /// applying [`Operator::Surround`].
+    ///
+    /// The counts are applied to the motion, as they can't be applied to the
+    /// surround action.
+    fn pend_surround(&mut self, actions: Actions) -> Actions {
+        if let Actions::List(list, _, typed) = &actions
+            && let [Action::Operator(Operator::Surround, scope, count)] = **list
+            && let Self::Pending(pre, reg, mid, _) = *self
+        {
+            let total = count
+                .saturating_mul(typed.unwrap_or(1))
+                .saturating_mul(pre.unwrap_or(1))
+                .saturating_mul(mid.unwrap_or(1));
+            *self = Self::Pending(
+                None,
+                reg,
+                None,
+                OPending::Surround(scope, total),
+            );
+            Actions::None
+        } else {
+            actions
+        }
+    }
+
+    /// Returns the operator that is waiting for its motion, if any.
+    pub const fn pending_operator(self) -> Option<Operator> {
+        if let Self::Pending(.., OPending::Operator(op, None, _)) = self {
+            Some(op)
+        } else {
+            None
+        }
+    }
+
+    /// Applies the counts and the register typed before a key to the actions
+    /// returned by `handle` for that key.
+    fn with_counts(
+        &mut self,
+        handle: impl FnOnce(&mut Self) -> Actions,
+    ) -> Actions {
+        match *self {
+            Self::None => handle(self),
+            Self::PreNum(num) => handle(self).repeat(num),
+            Self::Register(num, reg) =>
+                handle(self).with_reg(reg).repeat(num.unwrap_or(1)),
+            Self::MidNum(pre, reg, mid) => handle(self)
+                .repeat(pre.unwrap_or(1).saturating_mul(mid))
+                .with_reg(reg),
+            Self::Pending(pre, reg, mid, _) => handle(self)
+                .with_reg(reg)
+                .repeat(pre.unwrap_or(1).saturating_mul(mid.unwrap_or(1))),
+        }
+    }
 }
 
 impl Normal {
+    /// Handles a digit typed in normal mode, that continues a count, or
+    /// moves to the beginning of the line for a `0` that doesn't.
+    fn digit(&mut self, ch: char) -> Actions {
+        if ch == '0' && !matches!(self, Self::PreNum(..) | Self::MidNum(..)) {
+            GoToAction::BeginningOfLine.into()
+        } else {
+            self.num(ch)
+        }
+    }
+
     /// Handles opending event for [`CombinablePending`]
     const fn handle_combinable_opending_char_event(
         combinable_pending: CombinablePending,
@@ -93,6 +184,60 @@ impl Normal {
         }
     }
 
+    /// Handles a keymap registered by the host, before the counts are
+    /// applied.
+    ///
+    /// After an operator, motions and text objects give its scope, and the
+    /// operator itself applies it on whole lines.
+    fn handle_custom_once(&mut self, keymap: CustomKeymap) -> Actions {
+        match (*self, keymap) {
+            (Self::Pending(.., OPending::Operator(op, None, num)), _) => {
+                let scope = match keymap {
+                    CustomKeymap::Motion(id) => GoToAction::Custom(id).into(),
+                    CustomKeymap::Object(id) => OperatorScope::Custom(id),
+                    CustomKeymap::Operator(id)
+                        if op == Operator::Custom(id) =>
+                        OperatorScope::WholeLine,
+                    CustomKeymap::Operator(_) => return Actions::Unsupported,
+                };
+                let actions = actions![(op, scope)].repeat(num.unwrap_or(1));
+                self.pend_surround(actions)
+            }
+            (Self::Pending(..), _) | (_, CustomKeymap::Object(_)) =>
+                Actions::Unsupported,
+            (_, CustomKeymap::Motion(id)) => GoToAction::Custom(id).into(),
+            (_, CustomKeymap::Operator(id)) => self.pend(Operator::Custom(id)),
+        }
+    }
+
+    /// Handles a keypress after `g`, like `ge`, `gi` or `gU`.
+    fn handle_goto(&mut self, ch: char) -> Actions {
+        match ch {
+            'e' => GoToAction::EndOfPreviousWord.into(),
+            'E' => GoToAction::EndOfPreviousWORD.into(),
+            'c' => self.pend(Operator::Comment),
+            'J' => Action::Join(Join::Raw, 1).into(),
+            'i' => actions![
+                GoToAction::LastInsert,
+                Action::StartInsert(Mode::Insert, 1)
+            ],
+            ';' => Action::ChangeList(true).into(),
+            ',' => Action::ChangeList(false).into(),
+            _ => Operator::maybe_from(ch)
+                .filter(|op| {
+                    !matches!(
+                        op,
+                        Operator::Dedent
+                            | Operator::Exchange
+                            | Operator::Filter
+                            | Operator::Indent
+                            | Operator::Surround
+                    )
+                })
+                .map_or(Actions::Unsupported, |op| self.pend(op)),
+        }
+    }
+
     /// Handle a keypress when an [`OPending`] is in progress and waiting for
     /// keys.
     fn handle_opending_event(
@@ -102,10 +247,11 @@ impl Normal {
         ch: char,
     ) -> Actions {
         match opending {
-            OPending::GoTo if ch == 'e' => GoToAction::EndOfPreviousWord.into(),
-            OPending::GoTo if ch == 'E' => GoToAction::EndOfPreviousWORD.into(),
-            OPending::GoTo => Operator::maybe_from(ch)
-                .map_or(Actions::Unsupported, |op| self.pend(op)),
+            OPending::Bracket(_) | OPending::GoTo
+                if matches!(ch, 'p' | 'P') =>
+                Self::handle_paste(opending, ch),
+            OPending::Bracket(_) => Actions::Unsupported,
+            OPending::GoTo => self.handle_goto(ch),
             OPending::CombinablePending(action) => {
                 let (first, maybe_second) =
                     Self::handle_combinable_opending_char_event(action, ch);
@@ -115,29 +261,13 @@ impl Normal {
                 )
             }
             OPending::ReplaceOne => Action::ReplaceWith(ch).into(),
+            OPending::ChangeSurround(_)
+            | OPending::DeleteSurround
+            | OPending::Surround(..) => self.handle_surround(opending, ch),
             OPending::OperatorAction(op, combinable) =>
                 Self::handle_operator_action(op, combinable, ch),
-            OPending::Operator(op, None, num) => {
-                if let Some(scope) = OperatorPendingScope::maybe_from(ch, num) {
-                    self.pend(OPending::Operator(op, Some(scope), None))
-                } else if ch == op.as_char() {
-                    actions![(op, OperatorScope::WholeLine)]
-                        .repeat(num.unwrap_or(1))
-                } else if matches!(ch, '1'..='9') || num.is_some() && ch == '0'
-                {
-                    self.pend(OPending::Operator(
-                        op,
-                        None,
-                        Some(
-                            num.unwrap_or(0)
-                                .saturating_mul(10)
-                                .saturating_add(char_to_int_unchecked(ch)),
-                        ),
-                    ))
-                } else {
-                    self.handle_operator(event, op).repeat(num.unwrap_or(1))
-                }
-            }
+            OPending::Operator(op, None, num) =>
+                self.handle_operator_pending(op, num, event, ch),
             OPending::Operator(op, Some(scope), num) =>
                 Delimitation::maybe_from(ch).map_or(
                     Actions::Unsupported,
@@ -163,7 +293,7 @@ impl Normal {
                     Actions::Unsupported
                 }
             }
-            Actions::List(list, _) =>
+            Actions::List(list, ..) =>
                 if let &[list_action] = list.as_slice()
                     && let Action::GoTo(goto) = list_action
                 {
@@ -192,6 +322,69 @@ impl Normal {
         actions![(op, OperatorScope::Goto(first, second))]
     }
 
+    /// Handle a keypress after an operator that waits for its motion, scope
+    /// or count (`d`, `d2`, `c`, etc.)
+    fn handle_operator_pending(
+        &mut self,
+        op: Operator,
+        num: Option<usize>,
+        event: Event,
+        ch: char,
+    ) -> Actions {
+        if let Some(actions) = self.handle_operator_suffix(op, num, ch) {
+            return actions;
+        }
+        if let Some(scope) = OperatorPendingScope::maybe_from(ch, num) {
+            self.pend(OPending::Operator(op, Some(scope), None))
+        } else if op.as_char() == Some(ch) {
+            actions![(op, OperatorScope::WholeLine)].repeat(num.unwrap_or(1))
+        } else if matches!(ch, '1'..='9') || num.is_some() && ch == '0' {
+            self.pend(OPending::Operator(
+                op,
+                None,
+                Some(
+                    num.unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(char_to_int_unchecked(ch)),
+                ),
+            ))
+        } else {
+            self.handle_operator(event, op).repeat(num.unwrap_or(1))
+        }
+    }
+
+    /// Handles the keys that turn an operator into another command, like
+    /// `cx`, `cs`, `ds` and `ys`.
+    fn handle_operator_suffix(
+        &mut self,
+        op: Operator,
+        num: Option<usize>,
+        ch: char,
+    ) -> Option<Actions> {
+        Some(match (op, ch) {
+            (Operator::Change, 's') =>
+                self.pend(OPending::ChangeSurround(None)),
+            (Operator::Change, 'x') =>
+                self.pend(OPending::Operator(Operator::Exchange, None, num)),
+            (Operator::Delete, 's') => self.pend(OPending::DeleteSurround),
+            (Operator::Exchange, 'c') => Action::CancelExchange.into(),
+            (Operator::Yank, 's') =>
+                self.pend(OPending::Operator(Operator::Surround, None, num)),
+            _ => return None,
+        })
+    }
+
+    /// Handles the paste variants `gp`, `gP`, `]p` and `[p`
+    fn handle_paste(opending: OPending, ch: char) -> Actions {
+        let paste = match (opending, ch) {
+            (OPending::Bracket(']'), 'p') => Paste::AfterIndent,
+            (OPending::Bracket(_), _) => Paste::BeforeIndent,
+            (_, 'p') => Paste::AfterSkip,
+            _ => Paste::BeforeSkip,
+        };
+        Action::Paste(paste, 1).into()
+    }
+
     /// Handles a keypress when [`Normal`] is [`Normal::Pending`]
     fn handle_pending(
         &mut self,
@@ -208,22 +401,63 @@ impl Normal {
             {
                 self.pend(new_op)
             } else {
-                self.handle_opending_event(opending, event, ch)
+                let actions = self.handle_opending_event(opending, event, ch);
+                self.pend_surround(actions)
             }
         } else {
             Actions::Unsupported
         }
     }
+
+    /// Handles the chars typed after `cs`, `ds` and `ys{motion}`, that give
+    /// the surroundings to find and to add.
+    fn handle_surround(&mut self, opending: OPending, ch: char) -> Actions {
+        match opending {
+            OPending::ChangeSurround(None) =>
+                Surrounding::maybe_from_target(ch)
+                    .map_or(Actions::Unsupported, |old| {
+                        self.pend(OPending::ChangeSurround(Some(old)))
+                    }),
+            OPending::ChangeSurround(Some(old)) =>
+                Self::surround_with(Tag::Change(old), ch),
+            OPending::DeleteSurround => Surrounding::maybe_from_target(ch)
+                .map_or(Actions::Unsupported, |old| {
+                    SurroundAction::Delete(old).into()
+                }),
+            OPending::Surround(scope, count) =>
+                Self::surround_with(Tag::Add(scope, count), ch),
+            OPending::Bracket(_)
+            | OPending::CombinablePending(_)
+            | OPending::GoTo
+            | OPending::Operator(..)
+            | OPending::OperatorAction(..)
+            | OPending::ReplaceOne => Actions::Unsupported,
+        }
+    }
+
+    /// Returns the surround action for the new surrounding typed after
+    /// `ys{motion}` or `cs{char}`, or prompts for a tag.
+    fn surround_with(tag: Tag, ch: char) -> Actions {
+        match Surrounding::maybe_from(ch) {
+            Some(Surrounding::Tag) => SurroundAction::StartTag(tag).into(),
+            Some(new) => tag.to_action(new).into(),
+            None => Actions::Unsupported,
+        }
+    }
 }
 
 #[expect(clippy::wildcard_enum_match_arm, reason = "only support a few")]
 impl HandleKeyPress for Normal {
     fn handle_blank_key_press(&mut self, code: KeyCode) -> Actions {
         match code {
+            KeyCode::Char('!') => self.pend(Operator::Filter),
             KeyCode::Char('$') => GoToAction::EndOfLine.into(),
-            KeyCode::Char('.') => Action::Repeat.into(),
+            KeyCode::Char('.') => Action::Repeat(None).into(),
             KeyCode::Char('^') => GoToAction::FirstNonSpace.into(),
-            KeyCode::Char('a') => actions![GoToAction::Right, Mode::Insert],
+            KeyCode::Char('a') => actions![
+                GoToAction::Right,
+                Action::StartInsert(Mode::Insert, 1)
+            ],
             KeyCode::Char('b') => GoToAction::BeginningOfWord.into(),
             KeyCode::Char('c') => self.pend(Operator::Change),
             KeyCode::Char('d') => self.pend(Operator::Delete),
@@ -232,14 +466,15 @@ impl HandleKeyPress for Normal {
             KeyCode::Char('g') => self.pend(OPending::GoTo),
             KeyCode::Char('h') | KeyCode::Backspace | KeyCode::Left =>
                 GoToAction::Left.into(),
-            KeyCode::Char('i') => Mode::Insert.into(),
+            KeyCode::Char('i') => Action::StartInsert(Mode::Insert, 1).into(),
             KeyCode::Char('l') | KeyCode::Right => GoToAction::NextChar.into(),
             KeyCode::Char('x') => actions![
                 (Operator::Delete, GoToAction::Right.into()),
                 GoToAction::Right,
                 GoToAction::Left
             ],
-            KeyCode::Char('p') => Action::PasteAfter.into(),
+            KeyCode::Char('o') => Action::OpenLine(true, 1).into(),
+            KeyCode::Char('p') => Action::Paste(Paste::After, 1).into(),
             KeyCode::Char('r') => self.pend(OPending::ReplaceOne),
             KeyCode::Char('s') => actions![
                 (Operator::Delete, GoToAction::Right.into()),
@@ -251,20 +486,23 @@ impl HandleKeyPress for Normal {
             KeyCode::Char('w') => GoToAction::NextWord.into(),
             KeyCode::Char('y') => self.pend(Operator::Yank),
             KeyCode::Char('%') => GoToAction::NextGroup.into(),
+            KeyCode::Tab => Action::JumpList(false).into(),
+            KeyCode::Char(ch @ ('[' | ']')) => self.pend(OPending::Bracket(ch)),
+            KeyCode::Char('<') => self.pend(Operator::Dedent),
+            KeyCode::Char('>') => self.pend(Operator::Indent),
             KeyCode::Char('~') => actions![
                 (Operator::ToggleCase, GoToAction::Right.into()),
                 GoToAction::NextChar
             ],
-            KeyCode::Char('0')
-                if !matches!(self, Self::PreNum(..) | Self::MidNum(..)) =>
-                GoToAction::BeginningOfLine.into(),
-            KeyCode::Char(ch @ '0'..='9') => self.num(ch),
+            KeyCode::Char(ch @ '0'..='9') => self.digit(ch),
             _ => Actions::Unsupported,
         }
     }
 
     fn handle_ctrl_key_press(&mut self, code: KeyCode) -> Actions {
         match code {
+            KeyCode::Char('i') => Action::JumpList(false).into(),
+            KeyCode::Char('o') => Action::JumpList(true).into(),
             KeyCode::Char('r') => Action::Redo.into(),
             _ => Actions::Unsupported,
         }
@@ -281,27 +519,22 @@ impl HandleKeyPress for Normal {
                 *self = Self::Register(Some(pre), None);
                 Actions::None
             }
-            Self::None => self.default_handle_key(event),
-            Self::PreNum(num) => self.default_handle_key(event).repeat(num),
+            Self::Register(pre, None) if ch == Some('=') =>
+                Action::StartExpression(Expression::Normal(pre)).into(),
             Self::Register(pre, None) if ch.is_some() => {
                 *self = Self::Register(pre, ch);
                 Actions::None
             }
-            Self::Register(num, reg @ Some(_)) => self
-                .default_handle_key(event)
-                .with_reg(reg)
-                .repeat(num.unwrap_or(1)),
-            Self::Register(..) => Actions::Unsupported,
-            Self::MidNum(pre, reg, mid) => self
-                .default_handle_key(event)
-                .repeat(pre.unwrap_or(1).saturating_mul(mid))
-                .with_reg(reg),
-            Self::Pending(pre, reg, mid, opending) => self
-                .handle_pending(event, mid.is_some(), opending) //TODO: number after
-                                                                //pending but before
-                                                                //here
-                .with_reg(reg)
-                .repeat(pre.unwrap_or(1).saturating_mul(mid.unwrap_or(1))),
+            Self::Register(_, None) => Actions::Unsupported,
+            // TODO: number after pending but before here
+            Self::Pending(_, _, mid, opending) => self.with_counts(|normal| {
+                normal.handle_pending(event, mid.is_some(), opending)
+            }),
+            Self::None
+            | Self::PreNum(_)
+            | Self::Register(_, Some(_))
+            | Self::MidNum(..) =>
+                self.with_counts(|normal| normal.default_handle_key(event)),
         };
         if actions != Actions::None {
             *self = Self::None;
@@ -311,7 +544,10 @@ impl HandleKeyPress for Normal {
 
     fn handle_shift_key_press(&mut self, code: KeyCode) -> Actions {
         match code {
-            KeyCode::Char('A') => actions![GoToAction::EndOfLine, Mode::Insert],
+            KeyCode::Char('A') => actions![
+                GoToAction::EndOfLine,
+                Action::StartInsert(Mode::Insert, 1)
+            ],
             KeyCode::Char('B') => GoToAction::BeginningOfWORD.into(),
             KeyCode::Char('C') => actions![
                 (Operator::Delete, GoToAction::EndOfLine.into()),
@@ -322,14 +558,16 @@ impl HandleKeyPress for Normal {
                     .into(),
             KeyCode::Char('E') => GoToAction::EndWORD.into(),
             KeyCode::Char('F') => self.pend(CombinablePending::FindPrevious),
-            KeyCode::Char('I') =>
-                actions![GoToAction::FirstNonSpace, Mode::Insert],
-            KeyCode::Char('P') => Action::PasteBefore.into(),
-            KeyCode::Char('R') => Mode::Replace.into(),
-            KeyCode::Char('S') => actions![
-                (Operator::Delete, OperatorScope::WholeLine),
-                Mode::Insert
+            KeyCode::Char('I') => actions![
+                GoToAction::FirstNonSpace,
+                Action::StartInsert(Mode::Insert, 1)
             ],
+            KeyCode::Char('J') => Action::Join(Join::Spaces, 1).into(),
+            KeyCode::Char('O') => Action::OpenLine(false, 1).into(),
+            KeyCode::Char('P') => Action::Paste(Paste::Before, 1).into(),
+            KeyCode::Char('R') => Action::StartInsert(Mode::Replace, 1).into(),
+            KeyCode::Char('S') =>
+                actions![(Operator::Change, OperatorScope::WholeLine)],
             KeyCode::Char('T') =>
                 self.pend(CombinablePending::FindPreviousIncrement),
             KeyCode::Char('W') => GoToAction::NextWORD.into(),
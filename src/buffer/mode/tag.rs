@@ -0,0 +1,63 @@
+use crossterm::event::KeyCode;
+
+use crate::buffer::keymaps::{
+    Action, OperatorScope, SurroundAction, Surrounding
+};
+use crate::buffer::macros::actions;
+use crate::buffer::mode::traits::{Actions, HandleKeyPress};
+
+/// Struct to handle keypresses while typing a tag for the surround commands,
+/// after `ys{motion}<` or `cs{char}<`.
+///
+/// It holds the surround command to perform once the tag is typed.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Tag {
+    /// Started with `ys{motion}`: the text of the motion is surrounded
+    Add(OperatorScope, usize),
+    /// Started with `cs{char}`: the surrounding is replaced by the tag
+    Change(Surrounding),
+}
+
+impl Tag {
+    /// Returns the surround action to perform with the new surrounding.
+    pub const fn to_action(self, new: Surrounding) -> Action {
+        match self {
+            Self::Add(scope, count) =>
+                Action::Surround(SurroundAction::Add(scope, count, new)),
+            Self::Change(old) =>
+                Action::Surround(SurroundAction::Change(old, new)),
+        }
+    }
+}
+
+#[expect(clippy::wildcard_enum_match_arm, reason = "only support a few")]
+impl HandleKeyPress for Tag {
+    fn handle_blank_key_press(&mut self, code: KeyCode) -> Actions {
+        match code {
+            KeyCode::Enter | KeyCode::Char('>') => actions![
+                SurroundAction::EndTag,
+                self.to_action(Surrounding::Tag)
+            ],
+            KeyCode::Esc => SurroundAction::CancelTag.into(),
+            KeyCode::Backspace => SurroundAction::TagBackspace.into(),
+            KeyCode::Char(ch) => SurroundAction::TagChar(ch).into(),
+            _ => Actions::Unsupported,
+        }
+    }
+
+    fn handle_ctrl_key_press(&mut self, _: KeyCode) -> Actions {
+        Actions::Unsupported
+    }
+
+    fn handle_shift_key_press(&mut self, code: KeyCode) -> Actions {
+        match code {
+            KeyCode::Char('>') => actions![
+                SurroundAction::EndTag,
+                self.to_action(Surrounding::Tag)
+            ],
+            KeyCode::Char(ch) =>
+                SurroundAction::TagChar(ch.to_ascii_uppercase()).into(),
+            _ => Actions::Unsupported,
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::buffer::keymaps::{
 };
 use crate::buffer::macros::actions;
 use crate::buffer::mode::all::Mode;
+use crate::buffer::mode::insert::Insert;
 use crate::buffer::mode::normal::Normal;
 use crate::buffer::mode::traits::HandleKeyPress;
 use crate::buffer::mode::{Actions, BufferMode};
@@ -37,7 +38,9 @@ fn event(
 
 fn test_insert_char(ch: char) {
     let event = code_event(KeyCode::Char(ch));
-    expect_action(BufferMode::Insert, event, &[Action::InsertChar(ch)]);
+    expect_action(BufferMode::Insert(Insert::None), event, &[
+        Action::InsertChar(ch),
+    ]);
 }
 
 #[test]
@@ -52,22 +55,22 @@ fn insert_char() {
 #[test]
 fn escape() {
     let event = code_event(KeyCode::Esc);
-    expect_action(BufferMode::Insert, event, &[
-        GoToAction::Left.into(),
+    expect_action(BufferMode::Insert(Insert::None), event, &[
         Mode::Normal.into(),
+        GoToAction::Left.into(),
     ]);
 }
 
 #[test]
 fn insert() {
     let event = code_event(KeyCode::Char('i'));
-    expect_action(NORMAL, event, &[Mode::Insert.into()]);
+    expect_action(NORMAL, event, &[Action::StartInsert(Mode::Insert, 1)]);
 }
 
 #[test]
 fn unsupported_key() {
     let event = code_event(KeyCode::Down);
-    expect_no_action(BufferMode::Insert, event);
+    expect_no_action(BufferMode::Insert(Insert::None), event);
     expect_no_action(NORMAL, event);
 }
 
@@ -87,15 +90,21 @@ fn with_modifiers_char() {
         KeyModifiers::META,
     ] {
         let event = event(KeyCode::Char('i'), Some(modifier), None);
-        expect_no_action(NORMAL, event);
-        expect_no_action(BufferMode::Insert, event);
+        if modifier == KeyModifiers::CONTROL {
+            expect_action(NORMAL, event, &[Action::JumpList(false)]);
+        } else {
+            expect_no_action(NORMAL, event);
+        }
+        expect_no_action(BufferMode::Insert(Insert::None), event);
     }
     let event = event(KeyCode::Char('i'), Some(KeyModifiers::SHIFT), None);
     expect_action(NORMAL, event, &[
         GoToAction::FirstNonSpace.into(),
-        Mode::Insert.into(),
+        Action::StartInsert(Mode::Insert, 1),
+    ]);
+    expect_action(BufferMode::Insert(Insert::None), event, &[
+        Action::InsertChar('I'),
     ]);
-    expect_action(BufferMode::Insert, event, &[Action::InsertChar('I')]);
 }
 
 #[test]
@@ -110,7 +119,7 @@ fn with_modifiers_esc() {
     ] {
         let event = event(KeyCode::Esc, Some(modifier), None);
         expect_no_action(NORMAL, event);
-        expect_no_action(BufferMode::Insert, event);
+        expect_no_action(BufferMode::Insert(Insert::None), event);
     }
 }
 
@@ -118,7 +127,7 @@ fn with_modifiers_esc() {
 fn not_press() {
     for kind in [KeyEventKind::Release, KeyEventKind::Repeat] {
         let event = event(KeyCode::Char('x'), None, Some(kind));
-        expect_no_action(BufferMode::Insert, event);
+        expect_no_action(BufferMode::Insert(Insert::None), event);
     }
 }
 
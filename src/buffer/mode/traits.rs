@@ -1,7 +1,9 @@
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
 use crate::Mode;
-use crate::buffer::keymaps::{Action, GoToAction};
+use crate::buffer::keymaps::{
+    Action, FilterAction, GoToAction, SurroundAction
+};
 use crate::buffer::macros::actions;
 
 /// Actions to be taken as a result of a keypress
@@ -9,8 +11,9 @@ use crate::buffer::macros::actions;
 pub enum Actions {
     /// List of buffer actions to be followed
     ///
-    /// The option is to hold the register used for this action
-    List(Vec<Action>, Option<char>),
+    /// The first option is to hold the register used for this action, and
+    /// the second one the count the actions were typed with, see [`scale`].
+    List(Vec<Action>, Option<char>, Option<usize>),
     /// No actions yet
     ///
     /// This happens when some state is pending, waiting for the next characters
@@ -21,24 +24,16 @@ pub enum Actions {
 
 impl Actions {
     /// Repeats the action `occurrences` times, if possible.
+    ///
+    /// The count is only applied by [`scale`] when the actions are
+    /// performed, so that `.` can apply another one.
     pub fn repeat(self, occurrences: usize) -> Self {
         match self {
-            Self::List(actions, reg) => {
-                if let [action] = actions.as_slice()
-                    && let Action::Operator(op, scope, old) = *action
-                {
-                    Self::List(
-                        vec![Action::Operator(
-                            op,
-                            scope,
-                            occurrences.saturating_mul(old),
-                        )],
-                        reg,
-                    )
-                } else {
-                    Self::List(actions.repeat(occurrences), reg)
-                }
-            }
+            Self::List(actions, reg, count) => Self::List(
+                actions,
+                reg,
+                Some(count.unwrap_or(1).saturating_mul(occurrences)),
+            ),
             Self::None | Self::Unsupported => self,
         }
     }
@@ -46,7 +41,7 @@ impl Actions {
     /// Adds or replaces the register in the list of actions
     pub fn with_reg(self, reg: Option<char>) -> Self {
         match self {
-            Self::List(actions, _) => Self::List(actions, reg),
+            Self::List(actions, _, count) => Self::List(actions, reg, count),
             Self::None | Self::Unsupported => self,
         }
     }
@@ -54,7 +49,7 @@ impl Actions {
 
 impl From<Vec<Action>> for Actions {
     fn from(value: Vec<Action>) -> Self {
-        Self::List(value, None)
+        Self::List(value, None, None)
     }
 }
 
@@ -76,6 +71,18 @@ impl From<GoToAction> for Actions {
     }
 }
 
+impl From<FilterAction> for Actions {
+    fn from(value: FilterAction) -> Self {
+        Action::from(value).into()
+    }
+}
+
+impl From<SurroundAction> for Actions {
+    fn from(value: SurroundAction) -> Self {
+        Action::from(value).into()
+    }
+}
+
 /// Handle incoming terminal events, like keypresses.
 pub trait HandleKeyPress {
     /// Handle incoming terminal events off any kind.
@@ -118,7 +125,7 @@ pub trait HandleKeyPress {
 
 /// Adds [`KeyModifiers::SHIFT`] if the event is a capital char, and capitalises
 /// the char if the modifiers contain shift.
-const fn fix_shift_modifier(key_event: &mut KeyEvent) {
+pub const fn fix_shift_modifier(key_event: &mut KeyEvent) {
     #[expect(clippy::else_if_without_else, reason = "checked")]
     if let KeyCode::Char(ch) = &mut key_event.code {
         if ch.is_ascii_uppercase() {
@@ -129,3 +136,34 @@ const fn fix_shift_modifier(key_event: &mut KeyEvent) {
         }
     }
 }
+
+/// Applies the count a list of actions was typed with.
+///
+/// The count of a single counted action is multiplied, like for `d3w` or
+/// `3p`, as is the one of the insertion that ends a list, like for `3A`.
+/// Other lists are repeated, like for `3x`. Without a count, the actions are
+/// left as they are, so that `.` keeps the count of the repeated command.
+pub fn scale(mut actions: Vec<Action>, count: Option<usize>) -> Vec<Action> {
+    let Some(occurrences) = count else { return actions };
+    match *actions.as_slice() {
+        [Action::Operator(op, scope, old)] =>
+            vec![Action::Operator(op, scope, occurrences.saturating_mul(old))],
+        [Action::Join(join, old)] =>
+            vec![Action::Join(join, occurrences.saturating_mul(old))],
+        [Action::OpenLine(below, old)] =>
+            vec![Action::OpenLine(below, occurrences.saturating_mul(old))],
+        [Action::Repeat(old)] => vec![Action::Repeat(Some(
+            occurrences.saturating_mul(old.unwrap_or(1)),
+        ))],
+        [.., Action::StartInsert(mode, old)] => {
+            if let Some(last) = actions.last_mut() {
+                *last =
+                    Action::StartInsert(mode, occurrences.saturating_mul(old));
+            }
+            actions
+        }
+        [Action::Paste(paste, old)] =>
+            vec![Action::Paste(paste, occurrences.saturating_mul(old))],
+        _ => actions.repeat(occurrences),
+    }
+}
@@ -0,0 +1,88 @@
+/// Vim options that change how the buffer is edited, set through the
+/// [`Buffer`](crate::Buffer) API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Start new lines with the indent of the current one, like vim's
+    /// `autoindent`
+    pub(super) autoindent: bool,
+    /// Template of a commented line for `gc`, where `%s` is replaced by the
+    /// line, like vim's `commentstring`
+    pub(super) commentstring: String,
+    /// Indent with spaces instead of tabs, like vim's `expandtab`
+    pub(super) expandtab: bool,
+    /// Flags that control automatic formatting, like vim's `formatoptions`
+    ///
+    /// Only `t` (auto-wrap text) and `c` (auto-wrap comments, continuing the
+    /// comment leader) are supported.
+    pub(super) formatoptions: String,
+    /// Number of columns of an indent level, like vim's `shiftwidth`
+    ///
+    /// `0` means the value of [`Self::tabstop`] is used.
+    pub(super) shiftwidth: usize,
+    /// Number of columns a tab counts for, like vim's `tabstop`
+    pub(super) tabstop: usize,
+    /// Maximum width of the lines for `gq` and auto-wrap, like vim's
+    /// `textwidth`
+    ///
+    /// `0` means lines are formatted to 79 columns, and never auto-wrapped.
+    pub(super) textwidth: usize,
+}
+
+impl Options {
+    /// Returns the width to wrap the lines to when typing, if auto-wrap is
+    /// enabled for text, or for comments if `comment` is `true`.
+    pub(super) fn auto_wrap_width(&self, comment: bool) -> Option<usize> {
+        let flag = if comment { 'c' } else { 't' };
+        (self.textwidth != 0 && self.formatoptions.contains(flag))
+            .then_some(self.textwidth)
+    }
+
+    /// Returns the width to format the lines to with `gq`.
+    pub(super) const fn format_width(&self) -> usize {
+        if self.textwidth == 0 { 79 } else { self.textwidth }
+    }
+
+    /// Returns the text of an indent of `width` columns.
+    ///
+    /// The indent is made of tabs and spaces, or only of spaces with
+    /// `expandtab`.
+    pub(super) fn indent_text(&self, width: usize) -> String {
+        if self.expandtab {
+            return " ".repeat(width);
+        }
+        let tabs = width.checked_div(self.tabstop).unwrap_or_default();
+        let spaces = width.checked_rem(self.tabstop).unwrap_or(width);
+        format!("{}{}", "\t".repeat(tabs), " ".repeat(spaces))
+    }
+
+    /// Returns the number of columns of an indent level.
+    pub(super) const fn shift(&self) -> usize {
+        if self.shiftwidth == 0 { self.tabstop } else { self.shiftwidth }
+    }
+
+    /// Returns the number of columns taken by `text`, where tabs reach the
+    /// next multiple of `tabstop`.
+    pub(super) fn width(&self, text: &str) -> usize {
+        text.chars().fold(0, |width, ch| {
+            if ch == '\t' {
+                width.saturating_add(1).next_multiple_of(self.tabstop.max(1))
+            } else {
+                width.saturating_add(1)
+            }
+        })
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            autoindent: false,
+            commentstring: "/* %s */".to_owned(),
+            expandtab: false,
+            formatoptions: "tcq".to_owned(),
+            shiftwidth: 8,
+            tabstop: 8,
+            textwidth: 0,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+/// Maximum number of positions remembered by a [`PositionList`], like in vim
+const CAPACITY: usize = 100;
+
+/// List of positions in the buffer that can be walked through, from oldest
+/// to newest, like the jump list and the change list.
+///
+/// A position is stored at most once, and the list never holds more than
+/// [`CAPACITY`] positions: the oldest ones are forgotten first.
+#[derive(Debug, Default, Clone)]
+pub struct PositionList {
+    /// Index of the position reached by walking the list, or the length of
+    /// the list if it isn't being walked
+    current: usize,
+    /// Positions of the list, from oldest to newest
+    positions: Vec<usize>,
+}
+
+impl PositionList {
+    /// Updates the positions after the text between `start` and `old_end`
+    /// was replaced by text ending at `new_end`, with [`adjust_position`],
+    /// and stops walking the list.
+    pub fn adjust(&mut self, start: usize, old_end: usize, new_end: usize) {
+        for position in &mut self.positions {
+            *position = adjust_position(*position, start, old_end, new_end);
+        }
+        let mut kept = Vec::with_capacity(self.positions.len());
+        for &position in self.positions.iter().rev() {
+            if !kept.contains(&position) {
+                kept.push(position);
+            }
+        }
+        kept.reverse();
+        self.current = kept.len();
+        self.positions = kept;
+    }
+
+    /// Returns `true` if the list is being walked, with [`Self::older`] or
+    /// [`Self::newer`].
+    pub const fn is_walking(&self) -> bool {
+        self.current < self.positions.len()
+    }
+
+    /// Returns the newest position of the list.
+    pub fn last(&self) -> Option<usize> {
+        self.positions.last().copied()
+    }
+
+    /// Moves to the next newer position that isn't `cursor`, and returns it.
+    pub fn newer(&mut self, cursor: usize) -> Option<usize> {
+        let (idx, &position) = self
+            .positions
+            .iter()
+            .enumerate()
+            .skip(self.current.saturating_add(1))
+            .find(|&(_, &position)| position != cursor)?;
+        self.current = idx;
+        Some(position)
+    }
+
+    /// Moves to the next older position that isn't `cursor`, and returns it.
+    pub fn older(&mut self, cursor: usize) -> Option<usize> {
+        let (idx, &position) = self
+            .positions
+            .get(..self.current)?
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|&(_, &position)| position != cursor)?;
+        self.current = idx;
+        Some(position)
+    }
+
+    /// Adds a position as the newest one, and stops walking the list.
+    ///
+    /// The position is removed from its older place in the list, if any.
+    pub fn push(&mut self, position: usize) {
+        self.positions.retain(|&old| old != position);
+        if self.positions.len() >= CAPACITY {
+            self.positions.remove(0);
+        }
+        self.positions.push(position);
+        self.current = self.positions.len();
+    }
+}
+
+/// Returns where `position` is after the text between `start` and `old_end`
+/// was replaced by text ending at `new_end`.
+///
+/// Positions after the replaced text are shifted, and positions inside it
+/// are moved to its start.
+pub fn adjust_position(
+    position: usize,
+    start: usize,
+    old_end: usize,
+    new_end: usize,
+) -> usize {
+    if position >= old_end {
+        position.saturating_sub(old_end).saturating_add(new_end)
+    } else {
+        position.min(start)
+    }
+}
@@ -0,0 +1,102 @@
+use alloc::sync::Arc;
+use core::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::buffer::registers::Registers;
+
+/// Handle to the registers used by a buffer
+#[derive(Debug)]
+pub enum RegisterHandle {
+    /// Registers owned by the buffer
+    Owned(Box<Registers>),
+    /// Registers shared with other buffers
+    Shared(Arc<Mutex<Registers>>),
+}
+
+impl RegisterHandle {
+    /// Gives access to the registers, locking them if they are shared.
+    pub fn lock(&mut self) -> RegistersGuard<'_> {
+        match self {
+            Self::Owned(registers) => RegistersGuard::Owned(registers),
+            Self::Shared(registers) => RegistersGuard::Shared(
+                registers.lock().unwrap_or_else(PoisonError::into_inner),
+            ),
+        }
+    }
+}
+
+impl Default for RegisterHandle {
+    fn default() -> Self {
+        Self::Owned(Box::default())
+    }
+}
+
+impl From<&SharedRegisters> for RegisterHandle {
+    fn from(value: &SharedRegisters) -> Self {
+        Self::Shared(Arc::clone(&value.0))
+    }
+}
+
+/// Access to the registers of a [`RegisterHandle`], that holds the lock
+/// while alive.
+pub enum RegistersGuard<'handle> {
+    /// Borrow of registers owned by the buffer
+    Owned(&'handle mut Registers),
+    /// Lock on registers shared between threads
+    Shared(MutexGuard<'handle, Registers>),
+}
+
+impl Deref for RegistersGuard<'_> {
+    type Target = Registers;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(registers) => registers,
+            Self::Shared(registers) => registers,
+        }
+    }
+}
+
+impl DerefMut for RegistersGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Owned(registers) => registers,
+            Self::Shared(registers) => registers,
+        }
+    }
+}
+
+/// Registers that can be shared between several buffers, on the same thread
+/// or on different ones.
+///
+/// Cloning the store gives another handle to the same registers. Give one to
+/// each buffer with [`crate::Buffer::use_shared_registers`] to yank in one
+/// buffer and paste in another, like vim's global registers. Each buffer
+/// keeps its own history and mode. The registers are locked while a buffer
+/// accesses them.
+///
+/// The clipboard provider and options are part of the store, and are thus
+/// shared as well.
+///
+/// # Examples
+///
+/// ```
+/// use vim_buffer::{Buffer, SharedRegisters};
+///
+/// let registers = SharedRegisters::default();
+/// let mut first = Buffer::from("abc def");
+/// first.use_shared_registers(&registers);
+/// first.update_from_string("\"ayw").unwrap();
+///
+/// let handle = registers.clone();
+/// std::thread::spawn(move || {
+///     let mut second = Buffer::default();
+///     second.use_shared_registers(&handle);
+///     second.update_from_string("\"ap").unwrap();
+///     assert_eq!(second.as_content(), "abc ");
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SharedRegisters(Arc<Mutex<Registers>>);
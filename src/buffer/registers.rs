@@ -1,3 +1,11 @@
+use core::fmt;
+use core::iter::once;
+use std::io::Write;
+
+use crate::buffer::clipboard::{
+    ClipboardOption, ClipboardProvider, ClipboardSelection, Osc52
+};
+
 /// Associates key name to value
 macro_rules! key {
     ($name:ident : $value:literal) => {
@@ -9,63 +17,254 @@ macro_rules! key {
 key!(DEFAULT: '"');
 key!(DELETE: '-');
 key!(COPY: '0');
+key!(FIRST_NUMBERED: '1');
+key!(LAST_NUMBERED: '9');
 
 /// Maximum number of registers
-const LEN: usize = 43;
+const LEN: usize = 46;
+
+/// Content of a register, with the way it must be pasted.
+#[derive(Debug, Clone)]
+struct Register {
+    /// Text held by the register
+    content: String,
+    /// Whether the text was yanked as chars, lines or a block.
+    ty: RegisterType,
+}
 
 #[doc = include_str!("registers.md")]
-#[derive(Debug)]
-pub struct Registers([Option<String>; LEN]);
+#[derive(Debug, Default)]
+pub struct Registers {
+    /// Behaviour of the unnamed register regarding the clipboard
+    clipboard_option: ClipboardOption,
+    /// Writer of OSC 52 sequences, sent when the clipboard registers are set
+    osc52: Option<Osc52>,
+    /// Provider of the content of the `+` and `*` registers
+    provider: Option<Box<dyn ClipboardProvider>>,
+    /// Content of the registers, indexed by [`Self::to_key`]
+    values: Values,
+}
 
 impl Registers {
-    /// Returns the value held by a register
+    /// Empties a register.
+    ///
+    /// Uppercase registers empty the corresponding lowercase register. The
+    /// clipboard registers also empty the clipboard.
+    ///
+    /// Returns `false` if the register can't be written to.
+    #[expect(clippy::indexing_slicing, reason = "to_key returns valid index")]
+    pub fn clear(&mut self, reg: char) -> bool {
+        if reg == '_' {
+            return true;
+        }
+        let Some((key, _)) = Self::to_key(reg, true) else {
+            return false;
+        };
+        self.values.0[key] = None;
+        ClipboardSelection::maybe_from(reg)
+            .is_none_or(|selection| self.write_clipboard(selection, ""))
+    }
+
+    /// Reads the clipboard selection from the provider, and stores it in the
+    /// register at the given key.
+    ///
+    /// Content that ends with a newline is linewise. If the provider fails,
+    /// the last known content is kept.
     #[expect(clippy::indexing_slicing, reason = "to_key returns valid index")]
-    pub fn get(&self, reg: Option<char>) -> Option<&str> {
-        reg.map_or_else(
-            || self.0[DEFAULT].as_deref(),
-            |ch| self.0[Self::to_key(ch, false)?.0].as_deref(),
-        )
+    fn fetch(&mut self, selection: ClipboardSelection, key: usize) {
+        let Some(text) =
+            self.provider.as_mut().and_then(|provider| provider.get(selection))
+        else {
+            return;
+        };
+        let register = text.strip_suffix('\n').map_or_else(
+            || Register { content: text.clone(), ty: RegisterType::Charwise },
+            |line| Register {
+                content: line.to_owned(),
+                ty: RegisterType::Linewise,
+            },
+        );
+        if self.values.0[key].as_ref().is_none_or(|old| {
+            old.content != register.content
+                || (old.ty == RegisterType::Linewise)
+                    != (register.ty == RegisterType::Linewise)
+        }) {
+            self.values.0[key] = Some(register);
+        }
+    }
+
+    /// Returns the value held by a register, and the way it must be pasted.
+    ///
+    /// The clipboard registers are read from the clipboard provider, if any.
+    #[expect(clippy::indexing_slicing, reason = "to_key returns valid index")]
+    pub fn get(&mut self, reg: Option<char>) -> Option<(&str, RegisterType)> {
+        let name = reg
+            .or_else(|| {
+                self.clipboard_option
+                    .as_selection()
+                    .map(ClipboardSelection::as_register)
+            })
+            .unwrap_or('"');
+        let key = Self::to_key(name, false)?.0;
+        if let Some(selection) = ClipboardSelection::maybe_from(name) {
+            self.fetch(selection, key);
+        }
+        self.values.0[key]
+            .as_ref()
+            .map(|register| (register.content.as_str(), register.ty))
     }
 
     /// Insert a new value at the given register
+    ///
+    /// Linewise deletes and deletes that span over multiple lines are big
+    /// deletes: they shift the numbered registers `"1` to `"9` and are stored
+    /// in `"1`, even when a register is given. Yanks and other deletes are
+    /// stored in `"0` and `"-` only when no register is given, or when the
+    /// register is the clipboard used by the unnamed register.
     pub fn insert(
         &mut self,
         value: &str,
         is_delete: bool,
+        ty: RegisterType,
         reg: Option<char>,
     ) -> bool {
         if reg == Some('_') {
             return true;
         }
-        self.insert_key(DEFAULT, value, false);
-        if is_delete {
-            self.insert_key(DELETE, value, false);
-        } else {
-            self.insert_key(COPY, value, false);
+        let unnamed = reg.is_none()
+            || reg
+                == self
+                    .clipboard_option
+                    .as_selection()
+                    .map(ClipboardSelection::as_register);
+        let big_delete =
+            is_delete && (ty == RegisterType::Linewise || value.contains('\n'));
+        self.insert_key(DEFAULT, value, ty, false);
+        if big_delete {
+            self.shift_numbered();
+            self.insert_key(FIRST_NUMBERED, value, ty, false);
+        }
+        if unnamed && !big_delete {
+            let key = if is_delete { DELETE } else { COPY };
+            self.insert_key(key, value, ty, false);
+        }
+        match reg {
+            Some(ch) => self.set(ch, value, ty),
+            None =>
+                self.clipboard_option.as_selection().is_none_or(|selection| {
+                    self.set(selection.as_register(), value, ty)
+                }),
         }
-        reg.is_none_or(|ch| {
-            Self::to_key(ch, true).is_some_and(|(key, append)| {
-                self.insert_key(key, value, append);
-                true
-            })
-        })
     }
 
     /// Insert a new value at the given register key
     ///
+    /// When appending, the register keeps its type.
+    ///
     /// # Panics
     ///
     /// If key >= 128.
     #[expect(clippy::indexing_slicing, reason = "keys are less than 128")]
-    fn insert_key(&mut self, key: usize, value: &str, append: bool) {
-        if let Some(old) = &mut self.0[key] {
+    fn insert_key(
+        &mut self,
+        key: usize,
+        value: &str,
+        ty: RegisterType,
+        append: bool,
+    ) {
+        if let Some(old) = &mut self.values.0[key] {
             if append {
-                old.push_str(value);
+                old.content.push_str(value);
             } else {
-                value.clone_into(old);
+                value.clone_into(&mut old.content);
+                old.ty = ty;
             }
         } else {
-            self.0[key] = Some(value.to_owned());
+            self.values.0[key] =
+                Some(Register { content: value.to_owned(), ty });
+        }
+    }
+
+    /// Returns the content and type of every register that isn't empty, in
+    /// the order of vim's `:registers`.
+    pub fn list(&mut self) -> Vec<RegisterEntry> {
+        once('"')
+            .chain('0'..='9')
+            .chain('a'..='z')
+            .chain(['-', '.', ':', '%', '#', '/', '=', '+', '*'])
+            .filter_map(|reg| {
+                self.get(Some(reg))
+                    .filter(|(content, _)| !content.is_empty())
+                    .map(|(content, ty)| (reg, content.to_owned(), ty))
+            })
+            .collect()
+    }
+
+    /// Replaces the content of every register by `values`, taken with
+    /// [`Self::values`].
+    ///
+    /// The clipboard provider isn't written to, so the `+` and `*` registers
+    /// are read again from it.
+    pub fn restore(&mut self, values: Values) {
+        self.values = values;
+    }
+
+    /// Sets the value of a register, without updating the unnamed register.
+    ///
+    /// The clipboard registers are also written to the clipboard provider and
+    /// sent as OSC 52 sequences, if enabled. Linewise content is written with a
+    /// trailing newline.
+    ///
+    /// Returns `false` if the register can't be written to.
+    pub fn set(&mut self, reg: char, value: &str, ty: RegisterType) -> bool {
+        if reg == '_' {
+            return true;
+        }
+        let Some((key, append)) = Self::to_key(reg, true) else {
+            return false;
+        };
+        self.insert_key(key, value, ty, append);
+        ClipboardSelection::maybe_from(reg).is_none_or(|selection| {
+            if ty == RegisterType::Linewise {
+                self.write_clipboard(selection, &format!("{value}\n"))
+            } else {
+                self.write_clipboard(selection, value)
+            }
+        })
+    }
+
+    /// Sets the behaviour of the unnamed register regarding the clipboard.
+    pub const fn set_clipboard_option(&mut self, option: ClipboardOption) {
+        self.clipboard_option = option;
+    }
+
+    /// Enables OSC 52 output: the clipboard registers are sent to `writer`
+    /// when they are set, if their encoded content is at most `max_len` bytes
+    /// long.
+    pub fn set_osc52(&mut self, writer: Box<dyn Write + Send>, max_len: usize) {
+        self.osc52 = Some(Osc52::new(writer, max_len));
+    }
+
+    /// Installs a provider for the clipboard registers `+` and `*`.
+    pub fn set_provider(&mut self, provider: Box<dyn ClipboardProvider>) {
+        self.provider = Some(provider);
+    }
+
+    /// Sets the value of a read-only register, like `.` or `%`, that can't be
+    /// written with [`Self::set`].
+    pub fn set_read_only(&mut self, reg: char, value: &str) {
+        if let Some((key, _)) = Self::to_key(reg, false) {
+            self.insert_key(key, value, RegisterType::Charwise, false);
+        }
+    }
+
+    /// Shifts the content of the numbered registers: `"1` goes to `"2`, `"2`
+    /// to `"3`, etc. The content of `"9` is lost.
+    fn shift_numbered(&mut self) {
+        if let Some(numbered) =
+            self.values.0.get_mut(FIRST_NUMBERED..=LAST_NUMBERED)
+        {
+            numbered.rotate_right(1);
         }
     }
 
@@ -83,19 +282,89 @@ impl Registers {
             '"' => 36,
             '-' => 37,
             '=' => 38,
+            '+' => 43,
+            '*' => 44,
             _ if edit => return None,
             '%' => 39,
             '#' => 40,
             ':' => 41,
             '/' => 42,
+            '.' => 45,
             _ => return None,
         };
         Some((key, false))
     }
+
+    /// Returns a copy of the content of every register, to be put back with
+    /// [`Self::restore`].
+    pub fn values(&self) -> Values {
+        self.values.clone()
+    }
+
+    /// Writes the clipboard selection to the provider, and sends it as an OSC
+    /// 52 sequence, if enabled.
+    ///
+    /// Returns `false` if the provider failed. The OSC 52 sequence is best
+    /// effort, and is silently skipped if it is too long.
+    fn write_clipboard(
+        &mut self,
+        selection: ClipboardSelection,
+        text: &str,
+    ) -> bool {
+        if let Some(osc52) = self.osc52.as_mut() {
+            osc52.send(selection, text);
+        }
+        self.provider
+            .as_mut()
+            .is_none_or(|provider| provider.set(selection, text))
+    }
 }
 
-impl Default for Registers {
+/// Content of all the registers
+#[derive(Debug, Clone)]
+pub struct Values([Option<Register>; LEN]);
+
+impl Default for Values {
     fn default() -> Self {
         Self([const { None }; LEN])
     }
 }
+
+/// Name, content and type of a register, as listed by
+/// [`crate::Buffer::list_registers`]
+pub type RegisterEntry = (char, String, RegisterType);
+
+/// Type of the content of a register, that defines how it is pasted.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegisterType {
+    /// Content is a block, pasted column by column on successive lines.
+    Blockwise,
+    /// Content is a part of a line, pasted inline.
+    #[default]
+    Charwise,
+    /// Content is made of whole lines, pasted as new lines.
+    Linewise,
+}
+
+impl fmt::Display for RegisterType {
+    /// Displays the type with the letter used by `:registers`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::RegisterType;
+    ///
+    /// assert_eq!(RegisterType::Charwise.to_string(), "c");
+    /// assert_eq!(RegisterType::Linewise.to_string(), "l");
+    /// assert_eq!(RegisterType::Blockwise.to_string(), "b");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blockwise => 'b',
+            Self::Charwise => 'c',
+            Self::Linewise => 'l',
+        }
+        .fmt(f)
+    }
+}
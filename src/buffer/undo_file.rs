@@ -0,0 +1,217 @@
+use core::error::Error;
+use core::{fmt, num};
+use std::io::{self, Read, Write};
+
+use crate::Buffer;
+use crate::buffer::history::History;
+
+/// Key of the header line that holds the cursor in the history
+const CURSOR_KEY: &str = "cursor";
+
+/// Key of the header line that holds the number of entries
+const ENTRIES_KEY: &str = "entries";
+
+/// Key of the line that precedes each entry, and holds its length
+const ENTRY_KEY: &str = "entry";
+
+/// Key of the header line that holds the hash of the content
+const HASH_KEY: &str = "hash";
+
+/// First line of every undo file, to recognise the format.
+const MAGIC: &str = "vim-buffer undofile";
+
+/// Version of the undo file format written by this crate.
+const VERSION: u32 = 1;
+
+/// Key of the header line that holds the format version
+const VERSION_KEY: &str = "version";
+
+impl Buffer {
+    /// Restores the history of the buffer from an undo file, written by
+    /// [`Self::write_undo_file`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reader fails, if the file is not a valid undo
+    /// file, or if it was written for a content that is different from the
+    /// current one. In that case, the history is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::{Buffer, UndoFileError};
+    ///
+    /// let mut buffer = Buffer::default();
+    /// buffer.update_from_string("iabc<Esc>adef<Esc>").unwrap();
+    /// let mut file = vec![];
+    /// buffer.write_undo_file(&mut file).unwrap();
+    ///
+    /// let mut reopened = Buffer::from("abcdef");
+    /// reopened.read_undo_file(file.as_slice()).unwrap();
+    /// reopened.update_from_string("u").unwrap();
+    /// assert_eq!(reopened.as_content(), "abc");
+    ///
+    /// let mut edited = Buffer::from("abcdefghi");
+    /// assert!(matches!(
+    ///     edited.read_undo_file(file.as_slice()),
+    ///     Err(UndoFileError::HashMismatch)
+    /// ));
+    /// ```
+    pub fn read_undo_file<R: Read>(
+        &mut self,
+        mut reader: R,
+    ) -> Result<(), UndoFileError> {
+        let mut file = String::new();
+        reader.read_to_string(&mut file)?;
+
+        let mut rest = file.as_str();
+        if next_line(&mut rest)? != MAGIC {
+            return Err(UndoFileError::InvalidHeader);
+        }
+        let version = next_value(&mut rest, VERSION_KEY)?.parse()?;
+        if version != VERSION {
+            return Err(UndoFileError::UnsupportedVersion(version));
+        }
+        let hash = u64::from_str_radix(next_value(&mut rest, HASH_KEY)?, 16)?;
+        if hash != fnv1a(&self.content) {
+            return Err(UndoFileError::HashMismatch);
+        }
+        let cursor = next_value(&mut rest, CURSOR_KEY)?.parse()?;
+        let len: usize = next_value(&mut rest, ENTRIES_KEY)?.parse()?;
+
+        let mut entries = Vec::new();
+        for _ in 0..len {
+            let entry_len = next_value(&mut rest, ENTRY_KEY)?.parse()?;
+            let entry =
+                rest.get(..entry_len).ok_or(UndoFileError::Truncated)?;
+            rest = rest
+                .get(entry_len..)
+                .and_then(|after| after.strip_prefix('\n'))
+                .ok_or(UndoFileError::Truncated)?;
+            entries.push(Box::from(entry));
+        }
+        if !rest.is_empty() {
+            return Err(UndoFileError::Malformed);
+        }
+
+        self.history = History::from_parts(entries, cursor)
+            .ok_or(UndoFileError::CursorOutOfBounds)?;
+        Ok(())
+    }
+
+    /// Writes the whole history of the buffer to an undo file, that can later
+    /// be restored with [`Self::read_undo_file`].
+    ///
+    /// The file is a text file, that starts with a header holding the version
+    /// of the format, a hash of the current content, the position in the
+    /// history and the number of entries. Every entry follows, prefixed by its
+    /// length in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the writer failed.
+    pub fn write_undo_file<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let entries = self.history.as_vec();
+        writeln!(writer, "{MAGIC}")?;
+        writeln!(writer, "{VERSION_KEY} {VERSION}")?;
+        writeln!(writer, "{HASH_KEY} {:016x}", fnv1a(&self.content))?;
+        writeln!(writer, "{CURSOR_KEY} {}", self.history.as_cursor())?;
+        writeln!(writer, "{ENTRIES_KEY} {}", entries.len())?;
+        for entry in entries {
+            writeln!(writer, "{ENTRY_KEY} {}", entry.len())?;
+            writeln!(writer, "{entry}")?;
+        }
+        writer.flush()
+    }
+}
+
+/// Errors that may occur when reading an undo file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UndoFileError {
+    /// The history position is not a valid entry.
+    CursorOutOfBounds,
+    /// The undo file was written for another content.
+    HashMismatch,
+    /// The file doesn't start with the undo file header.
+    InvalidHeader,
+    /// Failed to read the undo file.
+    Io(io::Error),
+    /// A line of the file doesn't have the expected format.
+    Malformed,
+    /// The file ended before all the entries could be read.
+    Truncated,
+    /// The undo file was written with an unknown version of the format.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for UndoFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CursorOutOfBounds => "history position out of bounds".fmt(f),
+            Self::HashMismatch =>
+                "undo file was written for a different content".fmt(f),
+            Self::InvalidHeader => "not an undo file".fmt(f),
+            Self::Io(err) => write!(f, "failed to read undo file: {err}"),
+            Self::Malformed => "malformed undo file".fmt(f),
+            Self::Truncated => "undo file is truncated".fmt(f),
+            Self::UnsupportedVersion(version) =>
+                write!(f, "unsupported undo file version {version}"),
+        }
+    }
+}
+
+impl Error for UndoFileError {}
+
+impl From<io::Error> for UndoFileError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<num::ParseIntError> for UndoFileError {
+    fn from(_: num::ParseIntError) -> Self {
+        Self::Malformed
+    }
+}
+
+/// Computes the 64-bit FNV-1a hash of the content.
+///
+/// The hash is computed by hand to be stable across Rust versions and
+/// platforms, as it is persisted in the file.
+fn fnv1a(content: &str) -> u64 {
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Reads the next line, and moves `rest` after that line.
+///
+/// # Errors
+///
+/// Returns an error if there are no more lines.
+fn next_line<'file>(
+    rest: &mut &'file str,
+) -> Result<&'file str, UndoFileError> {
+    let (line, after) =
+        rest.split_once('\n').ok_or(UndoFileError::Truncated)?;
+    *rest = after;
+    Ok(line)
+}
+
+/// Reads the value of the next `key value` line, and moves `rest` after that
+/// line.
+///
+/// # Errors
+///
+/// Returns an error if there are no more lines, or if the line doesn't start
+/// with `key`.
+fn next_value<'file>(
+    rest: &mut &'file str,
+    key: &str,
+) -> Result<&'file str, UndoFileError> {
+    next_line(rest)?
+        .strip_prefix(key)
+        .and_then(|value| value.strip_prefix(' '))
+        .ok_or(UndoFileError::Malformed)
+}
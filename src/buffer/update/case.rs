@@ -0,0 +1,125 @@
+use crate::Buffer;
+
+/// Pushes the conversion of a char, given the char before it in the buffer.
+pub(super) type Conversion = fn(&mut String, Option<char>, char);
+
+impl Buffer {
+    /// Converts the chars between `min` and `max` with a case operator, like
+    /// `gU`.
+    ///
+    /// A char can be converted into several ones, like `ß` that becomes `SS`
+    /// in capitals. The cursor is placed at `min`, or on the last char of
+    /// the conversion when a single char is converted, so that `~` moves
+    /// past it.
+    pub(super) fn convert_case(
+        &mut self,
+        min: usize,
+        max: usize,
+        convert: Conversion,
+    ) -> bool {
+        let Some(text) = self.content.get(min..max) else {
+            return false;
+        };
+        let mut previous =
+            self.content.get(..min).and_then(|before| before.chars().last());
+        let mut converted = String::with_capacity(text.len());
+        for ch in text.chars() {
+            convert(&mut converted, previous, ch);
+            previous = Some(ch);
+        }
+        let offset = if text.chars().nth(1).is_none() {
+            converted.char_indices().last().map_or(0, |(idx, _)| idx)
+        } else {
+            0
+        };
+        self.content.replace_range(min..max, &converted);
+        self.cursor.set_max(self.len());
+        self.cursor.set(min.saturating_add(offset));
+        true
+    }
+}
+
+/// Pushes the lower case of a char, like `gu`.
+pub(super) fn lower_case(out: &mut String, _: Option<char>, ch: char) {
+    out.extend(ch.to_lowercase());
+}
+
+/// Pushes the title case of a char.
+///
+/// It is its upper case, except for the chars that are titled differently in
+/// Unicode: the digraphs like `ǆ` that become `ǅ`, the ligatures like `ß` or
+/// `ﬁ` of which only the first letter is capitalised, the Greek letters with
+/// an iota subscript, that is kept, and the Georgian letters, that are left
+/// as they are.
+fn push_title_case(out: &mut String, ch: char) {
+    let shifted = |offset: u32| {
+        char::from_u32(u32::from(ch).saturating_add(offset)).unwrap_or(ch)
+    };
+    match ch {
+        '\u{1c4}'..='\u{1c6}' => out.push('\u{1c5}'),
+        '\u{1c7}'..='\u{1c9}' => out.push('\u{1c8}'),
+        '\u{1ca}'..='\u{1cc}' => out.push('\u{1cb}'),
+        '\u{1f1}'..='\u{1f3}' => out.push('\u{1f2}'),
+        '\u{df}' => out.push_str("Ss"),
+        '\u{587}' => out.push_str("\u{535}\u{582}"),
+        '\u{10d0}'..='\u{10fa}'
+        | '\u{10fd}'..='\u{10ff}'
+        | '\u{1f88}'..='\u{1f8f}'
+        | '\u{1f98}'..='\u{1f9f}'
+        | '\u{1fa8}'..='\u{1faf}'
+        | '\u{1fbc}'
+        | '\u{1fcc}'
+        | '\u{1ffc}' => out.push(ch),
+        '\u{1f80}'..='\u{1f87}'
+        | '\u{1f90}'..='\u{1f97}'
+        | '\u{1fa0}'..='\u{1fa7}' => out.push(shifted(8)),
+        '\u{1fb3}' | '\u{1fc3}' | '\u{1ff3}' => out.push(shifted(9)),
+        '\u{fb00}' => out.push_str("Ff"),
+        '\u{fb01}' => out.push_str("Fi"),
+        '\u{fb02}' => out.push_str("Fl"),
+        '\u{fb03}' => out.push_str("Ffi"),
+        '\u{fb04}' => out.push_str("Ffl"),
+        '\u{fb05}' | '\u{fb06}' => out.push_str("St"),
+        '\u{fb13}' => out.push_str("\u{544}\u{576}"),
+        '\u{fb14}' => out.push_str("\u{544}\u{565}"),
+        '\u{fb15}' => out.push_str("\u{544}\u{56b}"),
+        '\u{fb16}' => out.push_str("\u{54e}\u{576}"),
+        '\u{fb17}' => out.push_str("\u{544}\u{56d}"),
+        _ => out.extend(ch.to_uppercase()),
+    }
+}
+
+/// Pushes a char rotated by 13 letters if it is an ASCII letter, like `g?`.
+pub(super) fn rot13(out: &mut String, _: Option<char>, ch: char) {
+    let rotated = match ch {
+        'a'..='m' | 'A'..='M' => u32::from(ch).saturating_add(13),
+        'n'..='z' | 'N'..='Z' => u32::from(ch).saturating_sub(13),
+        _ => u32::from(ch),
+    };
+    out.push(char::from_u32(rotated).unwrap_or(ch));
+}
+
+/// Pushes the title case of a char if it starts a word, and its lower case
+/// otherwise, like `gz`.
+pub(super) fn title_case(out: &mut String, previous: Option<char>, ch: char) {
+    if previous.is_some_and(char::is_alphanumeric) {
+        out.extend(ch.to_lowercase());
+    } else {
+        push_title_case(out, ch);
+    }
+}
+
+/// Pushes the lower case of a capital, or the upper case of any other char,
+/// like `~`.
+pub(super) fn toggle_case(out: &mut String, _: Option<char>, ch: char) {
+    if ch.is_uppercase() {
+        out.extend(ch.to_lowercase());
+    } else {
+        out.extend(ch.to_uppercase());
+    }
+}
+
+/// Pushes the upper case of a char, like `gU`.
+pub(super) fn upper_case(out: &mut String, _: Option<char>, ch: char) {
+    out.extend(ch.to_uppercase());
+}
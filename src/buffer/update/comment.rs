@@ -0,0 +1,89 @@
+use crate::Buffer;
+use crate::buffer::update::indent::indent_of;
+
+impl Buffer {
+    /// Comments the lines between `min` and `max` with `commentstring`, or
+    /// uncomments them if they are all commented, like `gc`.
+    ///
+    /// Blank lines are left unchanged, and don't prevent the other lines from
+    /// being uncommented. The comment leaders are aligned on the smallest
+    /// indent, and the cursor is placed on the first non-space char of the
+    /// first line.
+    pub(super) fn toggle_comments(&mut self, min: usize, max: usize) -> bool {
+        let Some((left, right)) = self.options.commentstring.split_once("%s")
+        else {
+            return false;
+        };
+        let (start, end) = self.lines_between(min, max);
+        let Some(lines) = self.content.get(start..end) else {
+            return false;
+        };
+        let mut non_blank = lines.split('\n').filter(|line| !is_blank(line));
+        let toggled =
+            if non_blank.all(|line| is_commented(line, left, right)) {
+                lines
+                    .split('\n')
+                    .map(|line| uncomment(line, left, right))
+                    .collect::<Vec<_>>()
+            } else {
+                let indent = lines
+                    .split('\n')
+                    .filter(|line| !is_blank(line))
+                    .map(|line| indent_of(line).len())
+                    .min()
+                    .unwrap_or_default();
+                lines
+                    .split('\n')
+                    .map(|line| comment(line, indent, left, right))
+                    .collect::<Vec<_>>()
+            }
+            .join("\n");
+        self.content.replace_range(start..end, &toggled);
+        self.cursor.set_max(self.len());
+        self.cursor.set(start.saturating_add(indent_of(&toggled).len()));
+        true
+    }
+}
+
+/// Returns `line` commented with the `left` and `right` parts of
+/// `commentstring`, with `left` inserted after `indent` bytes.
+///
+/// Blank lines are returned unchanged.
+fn comment(line: &str, indent: usize, left: &str, right: &str) -> String {
+    if is_blank(line) {
+        return line.to_owned();
+    }
+    let (before, text) = line.split_at_checked(indent).unwrap_or(("", line));
+    format!("{before}{left}{text}{right}")
+}
+
+/// Returns `true` if `line` is empty or only holds spaces.
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Returns `true` if `line` is commented with the `left` and `right` parts of
+/// `commentstring`, ignoring the spaces around them.
+fn is_commented(line: &str, left: &str, right: &str) -> bool {
+    let text = line.trim();
+    text.starts_with(left.trim()) && text.ends_with(right.trim())
+}
+
+/// Returns `line` without the `left` and `right` parts of `commentstring`,
+/// and the spaces they hold if they are present.
+fn uncomment(line: &str, left: &str, right: &str) -> String {
+    let indent = indent_of(line);
+    let mut text = line.get(indent.len()..).unwrap_or_default();
+    text = text
+        .strip_prefix(left)
+        .or_else(|| text.strip_prefix(left.trim()))
+        .unwrap_or(text);
+    if !right.is_empty() {
+        text = text.trim_end();
+        text = text
+            .strip_suffix(right)
+            .or_else(|| text.strip_suffix(right.trim()))
+            .unwrap_or(text);
+    }
+    format!("{indent}{text}")
+}
@@ -0,0 +1,60 @@
+use crate::Buffer;
+use crate::buffer::eval::evaluate;
+use crate::buffer::registers::RegisterType;
+
+impl Buffer {
+    /// Leaves the expression mode and forgets the typed expression.
+    pub(super) fn end_expression(&mut self, select_register: bool) {
+        self.expression = None;
+        self.mode.end_expression(select_register);
+    }
+
+    /// Evaluates the typed expression and stores its result in the `=`
+    /// register, then goes back to the mode the expression was started from.
+    ///
+    /// An empty expression keeps the previous result. Returns `false` if the
+    /// expression is invalid, in which case the register is left untouched.
+    /// Outside of the expression mode, for instance when repeating an insert
+    /// with `.`, this does nothing.
+    pub(super) fn evaluate_expression(&mut self) -> bool {
+        let Some(expression) = self.expression.take() else {
+            return true;
+        };
+        let result = if expression.trim().is_empty() {
+            Ok(())
+        } else {
+            evaluate(&expression).map(|value| {
+                self.registers.lock().set(
+                    '=',
+                    &value.to_string(),
+                    RegisterType::Charwise,
+                );
+            })
+        };
+        self.end_expression(result.is_ok());
+        result.is_ok()
+    }
+
+    /// Removes the last char of the typed expression.
+    ///
+    /// Leaves the expression mode if the expression is already empty, like
+    /// vim's command line.
+    pub(super) fn pop_expression(&mut self) -> bool {
+        match &mut self.expression {
+            Some(expression) if !expression.is_empty() => {
+                expression.pop();
+            }
+            Some(_) => self.end_expression(false),
+            None => return false,
+        }
+        true
+    }
+
+    /// Adds a char at the end of the typed expression.
+    pub(super) fn push_expression(&mut self, ch: char) -> bool {
+        self.expression.as_mut().is_some_and(|expression| {
+            expression.push(ch);
+            true
+        })
+    }
+}
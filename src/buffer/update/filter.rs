@@ -0,0 +1,96 @@
+use crate::buffer::filter::FilterError;
+use crate::buffer::keymaps::{FilterAction, OperatorScope};
+use crate::buffer::mode::{BufferMode, Filter};
+use crate::{Buffer, Mode};
+
+impl Buffer {
+    /// Leaves the filter prompt, and forgets the typed command.
+    fn end_filter(&mut self) {
+        self.filter_command = None;
+        if matches!(self.mode, BufferMode::Filter(_)) {
+            self.mode.switch_to(Mode::Normal);
+        }
+    }
+
+    /// Replaces the lines of the motion by the output of the filter, like
+    /// `!{motion}cmd<CR>`.
+    ///
+    /// The typed command is kept for `.`, that runs it again when no command
+    /// is being typed. If the filter fails, the buffer is left unchanged and
+    /// the error is kept for [`Self::take_filter_error`].
+    fn run_filter(&mut self, scope: OperatorScope, count: usize) -> bool {
+        if let Some(typed) = self.filter_command.take() {
+            self.end_filter();
+            self.last_filter = typed;
+        }
+        if self.last_filter.trim().is_empty() {
+            return false;
+        }
+        let cursor = self.as_cursor();
+        let Some((min, max)) = self.get_operator_indices(scope, count) else {
+            self.cursor.set(cursor);
+            return false;
+        };
+        let (start, end) = self.lines_between(min, max);
+        let input =
+            format!("{}\n", self.content.get(start..end).unwrap_or_default());
+        let result =
+            self.filter.as_mut().map_or(Err(FilterError::NoFilter), |filter| {
+                filter.filter(&self.last_filter, &input)
+            });
+        match result {
+            Ok(output) => {
+                self.content.replace_range(
+                    start..end,
+                    output.strip_suffix('\n').unwrap_or(&output),
+                );
+                self.cursor.set_max(self.len());
+                self.cursor.set(start);
+                true
+            }
+            Err(err) => {
+                self.filter_error = Some(err);
+                self.cursor.set(cursor);
+                false
+            }
+        }
+    }
+
+    /// Opens the prompt for the command of a filter, like `!!`.
+    ///
+    /// The motion is only applied once the command is typed, so the cursor
+    /// is placed back where it was before the motion.
+    pub(super) fn start_filter(
+        &mut self,
+        cursor: usize,
+        scope: OperatorScope,
+        count: usize,
+    ) -> bool {
+        self.cursor.set(cursor);
+        self.mode = BufferMode::Filter(Filter::new(scope, count));
+        self.filter_command = Some(String::new());
+        true
+    }
+
+    /// Applies an action of the filter prompt.
+    pub(super) fn update_filter(&mut self, action: FilterAction) -> bool {
+        match action {
+            FilterAction::Backspace => match &mut self.filter_command {
+                Some(typed) if !typed.is_empty() => {
+                    typed.pop();
+                }
+                Some(_) => self.end_filter(),
+                None => return false,
+            },
+            FilterAction::Cancel => self.end_filter(),
+            FilterAction::Char(ch) =>
+                return self.filter_command.as_mut().is_some_and(|typed| {
+                    typed.push(ch);
+                    true
+                }),
+            FilterAction::Run(scope, count) =>
+                return self.run_filter(scope, count),
+        }
+        true
+    }
+}
@@ -0,0 +1,195 @@
+use core::mem::replace;
+
+use crate::Buffer;
+use crate::buffer::update::indent::indent_of;
+
+/// Comment leaders kept at the beginning of the lines when formatting, like
+/// vim's `comments` option.
+///
+/// Longer leaders come first, so that `///` isn't read as `//`.
+const COMMENT_LEADERS: [&str; 6] = ["///", "//!", "//", "#", "--", ">"];
+
+/// Paragraph being formatted: the indent and comment leader of its first
+/// line, and its words
+type Paragraph<'text> = (&'text str, Vec<&'text str>);
+
+impl Buffer {
+    /// Breaks the current line before the word being typed if it goes past
+    /// `textwidth`, like vim's auto-wrap with `formatoptions+=t`.
+    ///
+    /// The new line starts with the indent and the comment leader of the
+    /// current line.
+    pub(super) fn auto_wrap(&mut self) {
+        let cursor = self.as_cursor();
+        let start = self.line_start(cursor);
+        let Some(typed) = self.content.get(start..cursor) else {
+            return;
+        };
+        let (prefix, text) = split_prefix(typed);
+        let Some(width) =
+            self.options.auto_wrap_width(!prefix.trim().is_empty())
+        else {
+            return;
+        };
+        if self.options.width(typed) <= width {
+            return;
+        }
+        let Some(space) = text.rfind([' ', '\t']) else {
+            return;
+        };
+        let kept = text.get(..space).unwrap_or_default().trim_end();
+        if kept.is_empty() {
+            return;
+        }
+        let text_start = start.saturating_add(prefix.len());
+        let break_start = text_start.saturating_add(kept.len());
+        let break_end = text_start.saturating_add(space).saturating_add(1);
+        let newline = format!("\n{prefix}");
+        self.content.replace_range(break_start..break_end, &newline);
+        self.cursor.set_max(self.len());
+        self.cursor.set(
+            cursor
+                .saturating_sub(break_end.saturating_sub(break_start))
+                .saturating_add(newline.len()),
+        );
+    }
+
+    /// Formats the lines between `min` and `max` to `textwidth`, like `gq`.
+    ///
+    /// The cursor is placed on the first non-space char of the last line, or
+    /// stays on the same char if `keep_cursor` holds the cursor before the
+    /// motion, like `gw`.
+    pub(super) fn format_lines(
+        &mut self,
+        min: usize,
+        max: usize,
+        keep_cursor: Option<usize>,
+    ) -> bool {
+        let (start, end) = self.lines_between(min, max);
+        let Some(lines) = self.content.get(start..end) else {
+            return false;
+        };
+        let formatted = self.format_text(lines);
+        let cursor = keep_cursor.map_or_else(
+            || {
+                let last = formatted
+                    .rfind('\n')
+                    .map_or(0, |idx| idx.saturating_add(1));
+                let indent =
+                    indent_of(formatted.get(last..).unwrap_or_default());
+                start.saturating_add(last).saturating_add(indent.len())
+            },
+            |cursor| keep_cursor_on_char(cursor, start, lines, &formatted),
+        );
+        self.content.replace_range(start..end, &formatted);
+        self.cursor.set_max(self.len());
+        self.cursor.set(cursor);
+        true
+    }
+
+    /// Returns `lines` wrapped to `textwidth`.
+    ///
+    /// Paragraphs are separated by blank lines, or by lines with another
+    /// indent or comment leader. The words of each paragraph are joined and
+    /// wrapped again, and every line keeps the indent and comment leader of
+    /// the first line of its paragraph.
+    fn format_text(&self, lines: &str) -> String {
+        let mut formatted = Vec::new();
+        let mut paragraph: Option<Paragraph<'_>> = None;
+        for line in lines.split('\n') {
+            let (prefix, text) = split_prefix(line);
+            let blank = text.trim().is_empty();
+            if (blank
+                || paragraph.as_ref().is_some_and(|(old, _)| *old != prefix))
+                && let Some((old, words)) = paragraph.take()
+            {
+                formatted.extend(self.wrap_words(old, &words));
+            }
+            if blank {
+                formatted.push(line.trim_end().to_owned());
+            } else {
+                paragraph
+                    .get_or_insert((prefix, Vec::new()))
+                    .1
+                    .extend(text.split_whitespace());
+            }
+        }
+        if let Some((prefix, words)) = paragraph {
+            formatted.extend(self.wrap_words(prefix, &words));
+        }
+        formatted.join("\n")
+    }
+
+    /// Joins `words` with spaces into lines that start with `prefix` and
+    /// don't exceed `textwidth`, unless a word is too long to fit.
+    fn wrap_words(&self, prefix: &str, words: &[&str]) -> Vec<String> {
+        let width = self.options.format_width();
+        let mut lines = Vec::new();
+        let mut current = prefix.to_owned();
+        let mut has_word = false;
+        for word in words {
+            let fits = self
+                .options
+                .width(&current)
+                .saturating_add(1)
+                .saturating_add(word.chars().count())
+                <= width;
+            match (has_word, fits) {
+                (true, false) =>
+                    lines.push(replace(&mut current, prefix.to_owned())),
+                (true, true) => current.push(' '),
+                (false, _) => (),
+            }
+            current.push_str(word);
+            has_word = true;
+        }
+        lines.push(current);
+        lines
+    }
+}
+
+/// Returns the index of the char of `new` that matches the char at `cursor`
+/// in `old`, both starting at `start`.
+///
+/// Chars are matched by counting the non-space chars before them, as
+/// formatting only changes spaces. A cursor outside of `old` is moved with
+/// the text around it.
+fn keep_cursor_on_char(
+    cursor: usize,
+    start: usize,
+    old: &str,
+    new: &str,
+) -> usize {
+    if cursor < start {
+        return cursor;
+    }
+    if cursor >= start.saturating_add(old.len()) {
+        return cursor.saturating_sub(old.len()).saturating_add(new.len());
+    }
+    let before = old
+        .get(..cursor.saturating_sub(start))
+        .unwrap_or(old)
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .count();
+    new.char_indices()
+        .filter(|(_, ch)| !ch.is_whitespace())
+        .nth(before)
+        .map_or(new.len(), |(idx, _)| idx)
+        .saturating_add(start)
+}
+
+/// Splits `line` into its indent and comment leader, with the spaces that
+/// follow them, and the rest of the line.
+fn split_prefix(line: &str) -> (&str, &str) {
+    let indent = indent_of(line).len();
+    let rest = line.get(indent..).unwrap_or_default();
+    let leader = COMMENT_LEADERS
+        .iter()
+        .find(|leader| rest.starts_with(**leader))
+        .map_or(0, |leader| leader.len());
+    let text = rest.get(leader..).unwrap_or_default();
+    let spaces = text.len().saturating_sub(text.trim_start().len());
+    line.split_at_checked(indent.saturating_add(leader).saturating_add(spaces))
+        .unwrap_or((line, ""))
+}
@@ -97,6 +97,24 @@ impl Buffer {
         }
     }
 
+    /// Moves the cursor to the first non space character, like `^`.
+    fn goto_first_non_space(&mut self) {
+        self.cursor.set(
+            self.as_content()
+                .char_indices()
+                .find(|(_idx, ch)| !ch.is_whitespace())
+                .map_or_else(|| self.len(), |(idx, _ch)| idx),
+        );
+    }
+
+    /// Moves the cursor to where insert mode was last left, like `gi`, if it
+    /// ever was.
+    fn goto_last_insert(&mut self) {
+        if let Some(idx) = self.marks.get('^') {
+            self.cursor.set(idx);
+        }
+    }
+
     /// Moves the cursor to the beginning of the next WORD.
     #[expect(non_snake_case, reason = "vim wording")]
     fn goto_next_WORD(&mut self) {
@@ -171,20 +189,17 @@ impl Buffer {
     #[must_use]
     pub(super) fn update_cursor(&mut self, goto_action: GoToAction) -> bool {
         match goto_action {
-            GoToAction::Right => drop(self.cursor.increment()),
-            GoToAction::NextChar =>
-                if self.as_cursor().saturating_add(1) < self.len() {
-                    self.cursor.increment();
-                },
-            GoToAction::Left => drop(self.cursor.decrement()),
+            GoToAction::Right => self.cursor.set(self.next_char_index()),
+            GoToAction::NextChar => {
+                let next = self.next_char_index();
+                if next < self.len() {
+                    self.cursor.set(next);
+                }
+            }
+            GoToAction::Left => self.cursor.set(self.previous_char_index()),
             GoToAction::BeginningOfLine => self.cursor.set(0),
             GoToAction::EndOfLine => self.cursor.set_to_max(),
-            GoToAction::FirstNonSpace => self.cursor.set(
-                self.as_content()
-                    .char_indices()
-                    .find(|(_idx, ch)| !ch.is_whitespace())
-                    .map_or_else(|| self.len(), |(idx, _ch)| idx),
-            ),
+            GoToAction::FirstNonSpace => self.goto_first_non_space(),
             GoToAction::NextOccurrenceOf(ch) => self.cursor.set(
                 if let Some((idx, _ch)) = self
                     .chars_after_cursor()
@@ -206,6 +221,7 @@ impl Buffer {
                     return false;
                 },
             ),
+            GoToAction::LastInsert => self.goto_last_insert(),
             GoToAction::NextWORD => self.goto_next_WORD(),
             GoToAction::NextWord => self.goto_next_word(),
             GoToAction::BeginningOfWORD => self.goto_beginning_of_WORD(),
@@ -215,6 +231,7 @@ impl Buffer {
             GoToAction::EndOfPreviousWord => self.goto_end_of_previous_word(),
             GoToAction::EndOfPreviousWORD => self.goto_end_of_previous_WORD(),
             GoToAction::NextGroup => return self.goto_next_group(),
+            GoToAction::Custom(id) => return self.goto_custom(id),
         }
         true
     }
@@ -1,10 +1,70 @@
+use crossterm::event::KeyEvent;
+
+use crate::buffer::last_action::LastAction;
+use crate::buffer::marks::Marks;
+use crate::buffer::mode::BufferMode;
+use crate::buffer::position_list::PositionList;
+use crate::buffer::registers::Values;
+use crate::buffer::update::jumps::changed_range;
+use crate::utils::bounded_usize::BoundedUsize;
 use crate::{Buffer, Mode};
 
+/// State of the buffer taken before a transaction, put back if it fails
+///
+/// It holds everything an edit can change, except the history, which
+/// transactions don't save to, and the options, mappings and filter provider,
+/// that are only changed by the host.
+struct Snapshot {
+    /// Positions of the latest changes
+    changes: PositionList,
+    /// Content of the buffer
+    content: String,
+    /// Position of the cursor
+    cursor: BoundedUsize,
+    /// Region pending for an exchange
+    exchange: Option<(usize, usize)>,
+    /// Expression being typed for the `=` register
+    expression: Option<String>,
+    /// Command being typed for a filter
+    filter_command: Option<String>,
+    /// Count of the insertion in progress
+    insert_count: usize,
+    /// Whether the insertion in progress is repeated on new lines
+    insert_new_lines: bool,
+    /// Start of the insert session
+    insert_start: usize,
+    /// Text typed since entering insert mode
+    inserted: String,
+    /// Positions before the latest jumps
+    jumps: PositionList,
+    /// Action repeated by `.`
+    last_action: LastAction,
+    /// Last command typed for a filter
+    last_filter: String,
+    /// Last tag typed for the surround commands
+    last_tag: String,
+    /// Automatic marks
+    marks: Marks,
+    /// Mode of the buffer, with the state of its pending keys
+    mode: BufferMode,
+    /// Keys typed that start a mapping
+    pending_keys: Vec<KeyEvent>,
+    /// Chars replaced in replace mode
+    pre_replace_content: Vec<Option<char>>,
+    /// Content of the registers
+    registers: Values,
+    /// Tag being typed for the surround commands
+    tag: Option<String>,
+}
+
 impl Buffer {
     /// Undos the latest undo
     pub(super) fn redo(&mut self) -> bool {
         if let Some(previous) = self.history.redo() {
+            let range = changed_range(&self.content, previous);
             self.content = previous.to_owned().into_string();
+            self.exchange = None;
+            self.track_change(range);
             self.cursor.set_max(self.len());
             true
         } else {
@@ -12,18 +72,173 @@ impl Buffer {
         }
     }
 
+    /// Puts back the state of the buffer taken by [`Self::snapshot`].
+    ///
+    /// The mode is set directly rather than with [`Self::select_mode`], as
+    /// the state that switching modes updates, like the `.` register and the
+    /// `'^` mark, is restored as well.
+    fn restore(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            changes,
+            content,
+            cursor,
+            exchange,
+            expression,
+            filter_command,
+            insert_count,
+            insert_new_lines,
+            insert_start,
+            inserted,
+            jumps,
+            last_action,
+            last_filter,
+            last_tag,
+            marks,
+            mode,
+            pending_keys,
+            pre_replace_content,
+            registers,
+            tag,
+        } = snapshot;
+        self.changes = changes;
+        self.content = content;
+        self.cursor = cursor;
+        self.exchange = exchange;
+        self.expression = expression;
+        self.filter_command = filter_command;
+        self.insert_count = insert_count;
+        self.insert_new_lines = insert_new_lines;
+        self.insert_start = insert_start;
+        self.inserted = inserted;
+        self.jumps = jumps;
+        self.last_action = last_action;
+        self.last_filter = last_filter;
+        self.last_tag = last_tag;
+        self.marks = marks;
+        self.mode = mode;
+        self.pending_keys = pending_keys;
+        self.pre_replace_content = pre_replace_content;
+        self.registers.lock().restore(registers);
+        self.tag = tag;
+    }
+
     /// Adds the current buffer to the history, if it is different from the
     /// last entry.
+    ///
+    /// As the buffer changed, the region pending for an exchange is
+    /// forgotten, and the position of the change is added to the change
+    /// list.
     pub(crate) fn save_to_history(&mut self) {
-        if matches!(self.as_mode(), Mode::Normal) {
-            self.history.save(&self.content);
+        if self.transaction_depth != 0
+            || !matches!(self.as_mode(), Mode::Normal)
+        {
+            return;
+        }
+        let range =
+            changed_range(self.history.as_cursor_entry(), &self.content);
+        if self.history.save(&self.content) {
+            self.exchange = None;
+            self.track_change(range);
+            self.changes.push(self.as_cursor());
+        }
+        self.marks.save_insert();
+    }
+
+    /// Takes the state of the buffer that edits can change, to be put back
+    /// with [`Self::restore`].
+    fn snapshot(&mut self) -> Snapshot {
+        Snapshot {
+            changes: self.changes.clone(),
+            content: self.content.clone(),
+            cursor: self.cursor,
+            exchange: self.exchange,
+            expression: self.expression.clone(),
+            filter_command: self.filter_command.clone(),
+            insert_count: self.insert_count,
+            insert_new_lines: self.insert_new_lines,
+            insert_start: self.insert_start,
+            inserted: self.inserted.clone(),
+            jumps: self.jumps.clone(),
+            last_action: self.last_action.clone(),
+            last_filter: self.last_filter.clone(),
+            last_tag: self.last_tag.clone(),
+            marks: self.marks.clone(),
+            mode: self.mode,
+            pending_keys: self.pending_keys.clone(),
+            pre_replace_content: self.pre_replace_content.clone(),
+            registers: self.registers.lock().values(),
+            tag: self.tag.clone(),
+        }
+    }
+
+    /// Runs a group of edits as a single history entry.
+    ///
+    /// Every event, keymap string and edit applied inside `edit` is saved as
+    /// one undo step if `edit` returns [`Ok`]. If it returns [`Err`], the
+    /// buffer is put back as it was before the transaction: its content,
+    /// cursor and mode, but also the action repeated by `.`, the registers,
+    /// the marks, the jump and change lists, and any insertion or prompt in
+    /// progress. The history is left untouched.
+    ///
+    /// The system clipboard isn't restored, so the `+` and `*` registers keep
+    /// what was copied during the transaction. Shared registers are restored
+    /// as a whole, even if another buffer wrote to them in the meantime.
+    ///
+    /// Transactions can be nested: only the outermost one saves to the
+    /// history.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error returned by `edit`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vim_buffer::Buffer;
+    ///
+    /// let mut buffer = Buffer::from("abc");
+    /// buffer
+    ///     .transaction(|buf| {
+    ///         buf.update_from_string("Adef<Esc>")?;
+    ///         buf.update_from_string("Aghi<Esc>")
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(buffer.as_content(), "abcdefghi");
+    ///
+    /// buffer.update_from_string("u").unwrap();
+    /// assert_eq!(buffer.as_content(), "abc");
+    ///
+    /// let res: Result<(), ()> = buffer.transaction(|buf| {
+    ///     buf.update_from_string("dd").unwrap();
+    ///     Err(())
+    /// });
+    /// assert!(res.is_err());
+    /// assert_eq!(buffer.as_content(), "abc");
+    /// assert_eq!(buffer.as_register('1'), None);
+    /// ```
+    pub fn transaction<T, E, F>(&mut self, edit: F) -> Result<T, E>
+    where F: FnOnce(&mut Self) -> Result<T, E> {
+        let snapshot = self.snapshot();
+
+        self.transaction_depth = self.transaction_depth.saturating_add(1);
+        let res = edit(self);
+        self.transaction_depth = self.transaction_depth.saturating_sub(1);
+
+        if res.is_err() {
+            self.restore(snapshot);
+        } else {
+            self.save_to_history();
         }
+        res
     }
 
     /// Pops from history the first different  buffer value
     pub(super) fn undo(&mut self) -> bool {
         if let Some(previous) = self.history.undo() {
+            let range = changed_range(&self.content, previous);
             self.content = previous.to_owned().into_string();
+            self.exchange = None;
+            self.track_change(range);
             self.cursor.set_max(self.len());
             true
         } else {
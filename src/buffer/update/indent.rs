@@ -0,0 +1,94 @@
+use crate::Buffer;
+
+impl Buffer {
+    /// Indents or dedents the current line by one level, like `<C-t>` and
+    /// `<C-d>` in insert mode.
+    ///
+    /// The cursor stays on the same char, or at the end of the indent if it
+    /// was inside it, and so does the start of the insert session.
+    pub(super) fn shift_current_line(&mut self, right: bool) -> bool {
+        let start = self.line_start(self.as_cursor());
+        let end = self.line_end(start);
+        let Some(line) = self.content.get(start..end) else {
+            return false;
+        };
+        let old_indent = indent_of(line).len();
+        let shifted = self.shift_line(line, right, true);
+        let new_indent = indent_of(&shifted).len();
+        let moved = |position: usize| {
+            start.saturating_add(new_indent).saturating_add(
+                position.saturating_sub(start).saturating_sub(old_indent),
+            )
+        };
+        let cursor = moved(self.as_cursor());
+        if (start..=end).contains(&self.insert_start) {
+            self.insert_start = moved(self.insert_start);
+        }
+        self.content.replace_range(start..end, &shifted);
+        self.cursor.set_max(self.len());
+        self.cursor.set(cursor);
+        true
+    }
+
+    /// Returns `line` with one more indent level, or one less, using the
+    /// `shiftwidth` and `expandtab` options.
+    ///
+    /// Empty lines are left empty unless `indent_empty` is `true`, as vim
+    /// doesn't indent them with `>>`.
+    fn shift_line(
+        &self,
+        line: &str,
+        right: bool,
+        indent_empty: bool,
+    ) -> String {
+        if line.is_empty() && !indent_empty {
+            return String::new();
+        }
+        let indent = indent_of(line);
+        let width = self.options.width(indent);
+        let shift = self.options.shift();
+        let new_width = if right {
+            width.saturating_add(shift)
+        } else {
+            width.saturating_sub(shift)
+        };
+        format!(
+            "{}{}",
+            self.options.indent_text(new_width),
+            line.get(indent.len()..).unwrap_or_default()
+        )
+    }
+
+    /// Indents or dedents every line between `min` and `max` by one level,
+    /// like `>>` and `<<`.
+    ///
+    /// The cursor is placed on the first non-space char of the first line.
+    pub(super) fn shift_lines(
+        &mut self,
+        min: usize,
+        max: usize,
+        right: bool,
+    ) -> bool {
+        let (start, end) = self.lines_between(min, max);
+        let Some(lines) = self.content.get(start..end) else {
+            return false;
+        };
+        let shifted = lines
+            .split('\n')
+            .map(|line| self.shift_line(line, right, false))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.content.replace_range(start..end, &shifted);
+        self.cursor.set_max(self.len());
+        self.cursor.set(start.saturating_add(indent_of(&shifted).len()));
+        true
+    }
+}
+
+/// Returns the leading spaces and tabs of `line`.
+pub(super) fn indent_of(line: &str) -> &str {
+    line.get(
+        ..line.len().saturating_sub(line.trim_start_matches([' ', '\t']).len()),
+    )
+    .unwrap_or_default()
+}
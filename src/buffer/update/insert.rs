@@ -0,0 +1,77 @@
+use core::mem::take;
+
+use crate::buffer::keymaps::{GoToAction, Operator};
+use crate::{Buffer, Mode};
+
+impl Buffer {
+    /// Deletes the char before the cursor in insert mode, like `<BS>`.
+    ///
+    /// The char is only removed from the text typed in this session, for the
+    /// `.` register, if it was typed after entering insert mode. Otherwise,
+    /// the start of the session is moved back with the text after it.
+    pub(super) fn backspace(&mut self) -> bool {
+        let len = self.len();
+        if !(self.update_cursor(GoToAction::Left)
+            && self.update_with_operator(
+                Operator::Delete,
+                GoToAction::Right.into(),
+                1,
+                None,
+            ))
+        {
+            return false;
+        }
+        if self.as_cursor() >= self.insert_start {
+            self.inserted.pop();
+        } else {
+            self.insert_start = self
+                .insert_start
+                .saturating_sub(len.saturating_sub(self.len()));
+        }
+        true
+    }
+
+    /// Repeats the text typed in insert or replace mode, when leaving it
+    /// after a count, like `3ifoo<Esc>`, `3o` or `3R`.
+    ///
+    /// After `{count}o`, each repetition is typed on a new line after the
+    /// current one.
+    pub(super) fn repeat_insert(&mut self) {
+        let count = take(&mut self.insert_count);
+        let new_lines = take(&mut self.insert_new_lines);
+        if count == 0 {
+            return;
+        }
+        let text = take(&mut self.inserted);
+        let replace = self.as_mode() == Mode::Replace;
+        for _ in 0..count {
+            if new_lines {
+                self.cursor.set(self.line_end(self.as_cursor()));
+                self.insert_newline();
+            }
+            self.type_text(&text, replace);
+        }
+        self.inserted = text;
+    }
+
+    /// Switches to insert or replace mode, and remembers to type the text
+    /// `count - 1` more times when leaving it, like `3i`.
+    pub(super) fn start_insert(&mut self, mode: Mode, count: usize) {
+        self.select_mode(mode);
+        self.insert_count = count.saturating_sub(1);
+        self.insert_new_lines = false;
+    }
+
+    /// Types `text` at the cursor, as in replace mode if `replace` is `true`,
+    /// or as in insert mode otherwise.
+    fn type_text(&mut self, text: &str, replace: bool) {
+        for ch in text.chars() {
+            if replace {
+                self.replace_ch(ch, true, true);
+                self.cursor.set(self.next_char_index());
+            } else {
+                self.insert_char(ch);
+            }
+        }
+    }
+}
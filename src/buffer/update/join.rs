@@ -0,0 +1,58 @@
+use crate::Buffer;
+use crate::buffer::keymaps::Join;
+
+impl Buffer {
+    /// Joins `count` lines, starting with the current one, like `J` and
+    /// `gJ`.
+    ///
+    /// At least two lines are joined, and `count` is reduced to the number
+    /// of lines available. With `J`, the indent of the joined lines is
+    /// removed, and a space is inserted, unless the line ends with
+    /// whitespace, or the next one is empty or starts with `)`. The cursor
+    /// is placed where the last lines were joined.
+    ///
+    /// Fails on the last line.
+    pub(super) fn join_lines(&mut self, join: Join, count: usize) -> bool {
+        let mut end = self.line_end(self.as_cursor());
+        if end >= self.len() {
+            return false;
+        }
+        let mut joint = end;
+        for _ in 1..count.max(2) {
+            if end >= self.len() {
+                break;
+            }
+            let next = end.saturating_add(1);
+            let (removed, separator) = match join {
+                Join::Raw => (next, ""),
+                Join::Spaces => self.join_separator(end, next),
+            };
+            self.content.replace_range(end..removed, separator);
+            joint = end;
+            end = self.line_end(end);
+        }
+        self.cursor.set_max(self.len());
+        self.cursor.set(joint);
+        true
+    }
+
+    /// Returns the end of the indent of the line that starts at `next`, and
+    /// the separator that replaces the `\n` at `end` and that indent, for
+    /// `J`.
+    fn join_separator(&self, end: usize, next: usize) -> (usize, &'static str) {
+        let line = self.content.get(next..self.line_end(next)).unwrap_or("");
+        let text = line.trim_start();
+        let removed =
+            next.saturating_add(line.len().saturating_sub(text.len()));
+        let current = self.content.get(self.line_start(end)..end).unwrap_or("");
+        if text.is_empty()
+            || text.starts_with(')')
+            || current.is_empty()
+            || current.ends_with(char::is_whitespace)
+        {
+            (removed, "")
+        } else {
+            (removed, " ")
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::Buffer;
+use crate::buffer::keymaps::GoToAction;
+
+impl Buffer {
+    /// Moves the cursor with a [`GoToAction`], and remembers the position it
+    /// started from in the jump list if the motion is a jump, like `%`.
+    ///
+    /// Returns `true` if the action was successful.
+    pub(super) fn goto(&mut self, goto_action: GoToAction) -> bool {
+        let cursor = self.as_cursor();
+        self.update_cursor(goto_action) && {
+            if goto_action.is_jump() && cursor != self.as_cursor() {
+                self.jumps.push(cursor);
+            }
+            true
+        }
+    }
+
+    /// Moves the cursor to an older or newer position of the change list,
+    /// like `g;` and `g,`.
+    pub(super) fn walk_changes(&mut self, older: bool) -> bool {
+        let cursor = self.as_cursor();
+        let position = if older {
+            self.changes.older(cursor)
+        } else {
+            self.changes.newer(cursor)
+        };
+        position.is_some_and(|idx| {
+            self.cursor.set(idx);
+            true
+        })
+    }
+
+    /// Moves the cursor to an older or newer position of the jump list, like
+    /// `<C-o>` and `<C-i>`.
+    ///
+    /// The position of the cursor is remembered before going back, so that
+    /// `<C-i>` can come back to it.
+    pub(super) fn walk_jumps(&mut self, older: bool) -> bool {
+        let cursor = self.as_cursor();
+        let position = if older {
+            if !self.jumps.is_walking() {
+                self.jumps.push(cursor);
+            }
+            self.jumps.older(cursor)
+        } else {
+            self.jumps.newer(cursor)
+        };
+        position.is_some_and(|idx| {
+            self.cursor.set(idx);
+            true
+        })
+    }
+}
+
+/// Returns the range of text that changed between `old` and `new`, as the
+/// start of the change, its end in `old` and its end in `new`.
+pub(super) fn changed_range(old: &str, new: &str) -> (usize, usize, usize) {
+    let start = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|&((_, old_ch), new_ch)| old_ch != new_ch)
+        .map_or_else(|| old.len().min(new.len()), |((idx, _), _)| idx);
+    let old_rest = old.get(start..).unwrap_or_default();
+    let new_rest = new.get(start..).unwrap_or_default();
+    let suffix: usize = old_rest
+        .chars()
+        .rev()
+        .zip(new_rest.chars().rev())
+        .take_while(|(old_ch, new_ch)| old_ch == new_ch)
+        .map(|(ch, _)| ch.len_utf8())
+        .sum();
+    (start, old.len().saturating_sub(suffix), new.len().saturating_sub(suffix))
+}
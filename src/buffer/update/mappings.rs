@@ -0,0 +1,94 @@
+use crossterm::event::Event;
+
+use crate::Buffer;
+use crate::buffer::mappings::{KeyMatch, MappingFn};
+use crate::buffer::mode::{Actions, BufferMode};
+
+impl Buffer {
+    /// Replaces the text between `min` and `max` by the result of a custom
+    /// operator.
+    ///
+    /// The buffer is left unchanged if the operator returns `None`.
+    pub(super) fn apply_custom_operator(
+        &mut self,
+        id: usize,
+        min: usize,
+        max: usize,
+    ) -> bool {
+        let Some(MappingFn::Operator(operator)) = self.mappings.get_mut(id)
+        else {
+            return false;
+        };
+        let Some(replacement) = self.content.get(min..max).and_then(operator)
+        else {
+            return false;
+        };
+        self.content.replace_range(min..max, &replacement);
+        self.cursor.set_max(self.len());
+        self.cursor.set(min);
+        true
+    }
+
+    /// Returns the indices that bound a custom text object around the
+    /// cursor.
+    ///
+    /// Returns `None` if the object wasn't found, or if its bounds aren't
+    /// valid indices of the buffer.
+    pub(super) fn get_custom_object_indices(
+        &mut self,
+        id: usize,
+    ) -> Option<(usize, usize)> {
+        let cursor = self.as_cursor();
+        let Some(MappingFn::Object(object)) = self.mappings.get_mut(id) else {
+            return None;
+        };
+        object(&self.content, cursor).filter(|&(min, max)| {
+            min <= max && self.content.get(min..max).is_some()
+        })
+    }
+
+    /// Moves the cursor with a custom motion.
+    ///
+    /// Returns `false` if the motion failed, or if it returned an index that
+    /// isn't valid in the buffer.
+    pub(super) fn goto_custom(&mut self, id: usize) -> bool {
+        let cursor = self.as_cursor();
+        let Some(MappingFn::Motion(motion)) = self.mappings.get_mut(id) else {
+            return false;
+        };
+        motion(&self.content, cursor)
+            .filter(|&new| self.content.is_char_boundary(new))
+            .is_some_and(|new| {
+                self.cursor.set(new);
+                true
+            })
+    }
+
+    /// Returns the actions of the first pending keys, or `None` if there are
+    /// no pending keys, or if they start a mapping and more keys are needed.
+    ///
+    /// If the pending keys don't start a mapping, the first one is handled
+    /// by the mode, and the next ones are looked up again.
+    pub(super) fn resolve_pending_keys(&mut self) -> Option<Actions> {
+        if self.pending_keys.is_empty() {
+            return None;
+        }
+        if let BufferMode::Normal(normal) = &mut self.mode
+            && normal.accepts_custom()
+        {
+            match self
+                .mappings
+                .find(&self.pending_keys, normal.pending_operator())
+            {
+                KeyMatch::Full(keymap) => {
+                    self.pending_keys.clear();
+                    return Some(normal.handle_custom(keymap));
+                }
+                KeyMatch::Prefix => return None,
+                KeyMatch::None => (),
+            }
+        }
+        let key = self.pending_keys.remove(0);
+        Some(self.mode.handle_event(Event::Key(key)))
+    }
+}
@@ -0,0 +1,18 @@
+use crate::Buffer;
+
+impl Buffer {
+    /// Updates the jump list, the change list and the marks after the
+    /// content changed, see [`changed_range`], and sets `'[` and `']` to the
+    /// changed text.
+    ///
+    /// [`changed_range`]: super::jumps::changed_range
+    pub(super) fn track_change(
+        &mut self,
+        (start, old_end, new_end): (usize, usize, usize),
+    ) {
+        self.jumps.adjust(start, old_end, new_end);
+        self.changes.adjust(start, old_end, new_end);
+        self.marks.adjust(start, old_end, new_end);
+        self.marks.set_change(&self.content, start, new_end);
+    }
+}
@@ -1,9 +1,41 @@
+/// Handles the case operators, like `gU` and `g?`
+mod case;
+/// Handles the comment operator, like `gcc`
+mod comment;
+/// Handles typing and evaluating expressions for the `=` register
+mod expression;
+/// Handles typing and running the command of a filter, like `!!sort`
+mod filter;
+/// Handles the formatting operators and auto-wrap, like `gq` and `gw`
+mod format;
 /// Handles updates that modify only the cursor position
 mod goto;
 /// Handle history actions (save, undo, redo)
 mod history;
+/// Handles the indent operators and keymaps, like `>>` and `<C-t>`
+mod indent;
+/// Handles counted insertions, like `3ifoo<Esc>`
+mod insert;
+/// Handles joining lines, like `J` and `gJ`
+mod join;
+/// Handles the jump list and the change list, like `<C-o>` and `g;`
+mod jumps;
+/// Handles the operators, motions and text objects registered by the host
+mod mappings;
+/// Handles the automatic marks, like `'[` and `'^`
+mod marks;
+/// Handles splitting and opening lines, like `<CR>` and `o`
+mod newline;
 /// Handles operator actions, like `dfx` and `ci(`
 mod operator;
+/// Handles the paragraph text objects, like `ip`
+mod paragraph;
+/// Handles pasting the content of registers
+mod paste;
+/// Handles the operators that replace text, like `gr` and `cx`
+mod replace;
+/// Handles the surround commands, like `ysiw)`, `cs"'` and `ds(`
+mod surround;
 /// Useful utils to interact with the [`Buffer`]
 mod utils;
 
@@ -11,25 +43,71 @@ use core::mem::take;
 
 use crossterm::event::Event;
 
-use crate::Buffer;
 use crate::buffer::keymaps::Action;
-use crate::buffer::mode::Actions;
+use crate::buffer::mappings::as_key;
+use crate::buffer::mode::{Actions, BufferMode, scale};
 use crate::event_parser::{EventParsingError, parse_events};
+use crate::{Buffer, Mode};
 
 impl Buffer {
-    /// Paste the copied content after the cursor
-    #[must_use]
-    #[expect(clippy::arithmetic_side_effects, reason = "smaller than len")]
-    fn paste_after(&mut self, reg: Option<char>) -> bool {
-        let pos = if self.as_cursor() >= self.len() {
-            self.len()
+    /// Applies the actions triggered by a key, and remembers them for `.`
+    ///
+    /// Returns `true` iff all the actions were successful.
+    fn apply_actions(&mut self, actions: Actions) -> bool {
+        match actions {
+            Actions::Unsupported => false,
+            Actions::None => true,
+            Actions::List(list, reg, count) =>
+                scale(list.clone(), count)
+                    .iter()
+                    .all(|action| self.update_once(*action, reg))
+                    && {
+                        self.last_action.update(
+                            list,
+                            count,
+                            self.as_mode(),
+                            reg,
+                        );
+                        true
+                    },
+        }
+    }
+
+    /// Inserts a char at the cursor, and wraps the line if it becomes too
+    /// long, according to `textwidth` and `formatoptions`.
+    ///
+    /// A line break is inserted with the indent of the current line, with
+    /// `autoindent`.
+    ///
+    /// The start of the insert session is moved with the text after it, see
+    /// [`Self::backspace`].
+    fn insert_char(&mut self, ch: char) {
+        self.inserted.push(ch);
+        let (cursor, len) = (self.as_cursor(), self.len());
+        if ch == '\n' {
+            self.insert_newline();
         } else {
-            self.as_cursor() + 1
-        };
-        self.registers.get(reg).is_some_and(|clip| {
-            self.content.insert_str(pos, clip);
-            true
-        })
+            self.content.insert(cursor, ch);
+            self.cursor.set_max(self.len());
+            self.cursor.set(cursor.saturating_add(ch.len_utf8()));
+            if !ch.is_whitespace() {
+                self.auto_wrap();
+            }
+        }
+        if cursor < self.insert_start {
+            self.insert_start = self
+                .insert_start
+                .saturating_add(self.len().saturating_sub(len));
+        }
+    }
+
+    /// Repeats the last action, like `.`, with the count given to `.` if
+    /// any.
+    fn repeat_last_action(&mut self, count: Option<usize>) -> bool {
+        let mut last = take(&mut self.last_action);
+        let ok = last.perform(self, count);
+        self.last_action = last;
+        ok
     }
 
     /// Remove the character under the current cursor and replace it by
@@ -60,6 +138,48 @@ impl Buffer {
         true
     }
 
+    /// Switches to a new mode.
+    ///
+    /// The text typed in insert or replace mode is repeated when leaving it
+    /// after a count, like `3ifoo<Esc>`. The text typed in insert mode is
+    /// then stored in the `.` register.
+    pub(super) fn select_mode(&mut self, mode: Mode) {
+        let typing =
+            |other: Mode| matches!(other, Mode::Insert | Mode::Replace);
+        if typing(self.as_mode()) && !typing(mode) {
+            self.repeat_insert();
+            self.marks.leave_insert(self.as_cursor());
+        }
+        match (self.as_mode(), mode) {
+            (Mode::Insert, Mode::Normal) if !self.inserted.is_empty() => self
+                .registers
+                .lock()
+                .set_read_only('.', &take(&mut self.inserted)),
+            (Mode::Normal, Mode::Insert | Mode::Replace) => {
+                self.inserted.clear();
+                self.insert_start = self.as_cursor();
+            }
+            _ => (),
+        }
+        self.mode.switch_to(mode);
+    }
+
+    /// Restores the char replaced before the cursor in replace mode, like
+    /// `<BS>`.
+    fn undo_replace(&mut self) -> bool {
+        self.inserted.pop();
+        match self.pre_replace_content.pop() {
+            Some(Some(ch)) =>
+                self.cursor.decrement() && self.replace_ch(ch, false, false),
+            Some(None) => {
+                let hadsome = self.content.pop().is_none();
+                self.cursor.set_max(self.content.len());
+                hadsome
+            }
+            None => self.cursor.decrement(),
+        }
+    }
+
     /// Updates the buffer with a terminal event
     ///
     /// # Returns
@@ -132,16 +252,20 @@ impl Buffer {
     }
 
     /// Same as [`Self::update`] but without updating the history.
+    ///
+    /// Keys that start a mapping are kept until the mapping is fully typed,
+    /// or until another key shows it isn't.
     pub fn update_no_save(&mut self, event: Event) -> bool {
-        match self.mode.handle_event(event) {
-            Actions::Unsupported => false,
-            Actions::None => true,
-            Actions::List(list, reg) =>
-                list.iter().all(|action| self.update_once(*action, reg)) && {
-                    self.last_action.update(list, self.as_mode());
-                    true
-                },
+        let Some(key) = as_key(&event) else {
+            let actions = self.mode.handle_event(event);
+            return self.apply_actions(actions);
+        };
+        self.pending_keys.push(key);
+        let mut success = true;
+        while let Some(actions) = self.resolve_pending_keys() {
+            success = self.apply_actions(actions) && success;
         }
+        success
     }
 
     /// Updates the buffer with one [`Action`]
@@ -154,48 +278,45 @@ impl Buffer {
         reg: Option<char>,
     ) -> bool {
         match action {
-            Action::InsertChar(ch) => {
-                self.content.insert(self.as_cursor(), ch);
-                self.cursor.increment_with_capacity_unchecked();
-            }
-            Action::SelectMode(mode) => self.mode.switch_to(mode),
+            Action::Backspace => return self.backspace(),
+            Action::InsertChar(ch) => self.insert_char(ch),
+            Action::SelectMode(mode) => self.select_mode(mode),
             Action::ReplaceWith(ch) =>
                 return self.replace_ch(ch, false, false),
-            Action::ReplaceOrInsert(ch) =>
-                return self.replace_ch(ch, true, true),
+            Action::ReplaceOrInsert(ch) => {
+                self.inserted.push(ch);
+                return self.replace_ch(ch, true, true);
+            }
             Action::ClearUndoReplace => self.pre_replace_content.clear(),
-            Action::UndoReplace =>
-                return match self.pre_replace_content.pop() {
-                    Some(Some(ch)) =>
-                        self.cursor.decrement()
-                            && self.replace_ch(ch, false, false),
-                    Some(None) => {
-                        let hadsome = self.content.pop().is_none();
-                        self.cursor.set_max(self.content.len());
-                        hadsome
-                    }
-                    None => self.cursor.decrement(),
-                },
+            Action::UndoReplace => return self.undo_replace(),
             Action::Undo => return self.undo(),
             Action::Redo => return self.redo(),
-            Action::GoTo(goto_action) =>
-                return self.update_cursor(goto_action),
+            Action::GoTo(goto_action) => return self.goto(goto_action),
             Action::Operator(op, scope, num) =>
                 return self.update_with_operator(op, scope, num, reg),
-            Action::PasteAfter => return self.paste_after(reg),
-            Action::PasteBefore =>
-                if let Some(clip) = self.registers.get(reg) {
-                    let idx = self.as_cursor().saturating_sub(1);
-                    self.content.insert_str(idx, clip);
-                } else {
-                    return false;
-                },
-            Action::Repeat => {
-                let last = take(&mut self.last_action);
-                let ok = last.perform(self);
-                self.last_action = last;
-                return ok;
+            Action::InsertRegister(name) => return self.insert_register(name),
+            Action::StartExpression(expression) => {
+                self.mode = BufferMode::Expression(expression);
+                self.expression = Some(String::new());
             }
+            Action::ExpressionChar(ch) => return self.push_expression(ch),
+            Action::ExpressionBackspace => return self.pop_expression(),
+            Action::CancelExpression => self.end_expression(false),
+            Action::CancelExchange => self.exchange = None,
+            Action::EvaluateExpression => return self.evaluate_expression(),
+            Action::Filter(filter) => return self.update_filter(filter),
+            Action::Surround(surround) =>
+                return self.update_surround(surround),
+            Action::Join(join, count) => return self.join_lines(join, count),
+            Action::ChangeList(older) => return self.walk_changes(older),
+            Action::JumpList(older) => return self.walk_jumps(older),
+            Action::OpenLine(below, count) =>
+                return self.open_line(below, count),
+            Action::Paste(paste, count) =>
+                return self.paste(paste, count, reg),
+            Action::Repeat(count) => return self.repeat_last_action(count),
+            Action::StartInsert(mode, count) => self.start_insert(mode, count),
+            Action::ShiftLine(right) => return self.shift_current_line(right),
         }
         true
     }
@@ -0,0 +1,55 @@
+use crate::buffer::update::indent::indent_of;
+use crate::{Buffer, Mode};
+
+impl Buffer {
+    /// Returns the indent of a new line that follows the one containing
+    /// `idx`: the indent of that line before `idx` with `autoindent`, or
+    /// nothing otherwise.
+    fn autoindent(&self, idx: usize) -> String {
+        if !self.options.autoindent {
+            return String::new();
+        }
+        self.content
+            .get(self.line_start(idx)..idx.min(self.line_end(idx)))
+            .map(indent_of)
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    /// Splits the line at the cursor, like `<CR>` in insert mode.
+    ///
+    /// With `autoindent`, the new line starts with the indent of the current
+    /// one.
+    pub(super) fn insert_newline(&mut self) {
+        let cursor = self.as_cursor();
+        let newline = format!("\n{}", self.autoindent(cursor));
+        self.content.insert_str(cursor, &newline);
+        self.cursor.set_max(self.len());
+        self.cursor.set(cursor.saturating_add(newline.len()));
+    }
+
+    /// Opens a new line below the current one if `below` is `true`, or above
+    /// it otherwise, and starts insert mode on it, like `o` and `O`.
+    ///
+    /// With `autoindent`, the new line starts with the indent of the current
+    /// one. If `count` is greater than 1, the text typed is repeated on
+    /// `count - 1` more lines when leaving insert mode.
+    pub(super) fn open_line(&mut self, below: bool, count: usize) -> bool {
+        let cursor = self.as_cursor();
+        let indent = self.autoindent(self.line_end(cursor));
+        let new = if below {
+            let end = self.line_end(cursor);
+            self.content.insert_str(end, &format!("\n{indent}"));
+            end.saturating_add(1)
+        } else {
+            let start = self.line_start(cursor);
+            self.content.insert_str(start, &format!("{indent}\n"));
+            start
+        };
+        self.cursor.set_max(self.len());
+        self.cursor.set(new.saturating_add(indent.len()));
+        self.start_insert(Mode::Insert, count);
+        self.insert_new_lines = true;
+        true
+    }
+}
@@ -4,6 +4,10 @@ use crate::buffer::is_indent::{IsIdentChar, IsSpace};
 use crate::buffer::keymaps::{
     Delimitation, GoToAction, Operator, OperatorScope
 };
+use crate::buffer::registers::RegisterType;
+use crate::buffer::update::case::{
+    lower_case, rot13, title_case, toggle_case, upper_case
+};
 use crate::utils::bounded_usize::BoundedUsize;
 use crate::{Buffer, Mode};
 
@@ -16,28 +20,46 @@ impl Buffer {
         &mut self,
         min_cursor: usize,
         max_cursor: usize,
+        ty: RegisterType,
         reg: Option<char>,
     ) -> bool {
-        let old_content = take(&mut self.content);
-        self.content.reserve(old_content.len());
-        #[expect(clippy::string_slice, reason = "non-ascii not yet supported")]
-        // TODO: add support for UTF-8
-        {
-            self.content.push_str(&old_content[0..min_cursor]);
-            self.content.push_str(&old_content[max_cursor..]);
-        };
-
         #[expect(clippy::string_slice, reason = "non-ascii not yet supported")]
         // TODO: add support for UTF-8
         if max_cursor != min_cursor {
-            self.registers.insert(
-                &old_content[min_cursor..max_cursor],
+            self.registers.lock().insert(
+                &self.content[min_cursor..max_cursor],
                 true,
+                ty,
                 reg,
             );
         }
-        self.cursor = BoundedUsize::with_capacity(self.content.len());
-        self.cursor.set(min_cursor);
+        self.remove(min_cursor, max_cursor);
+        true
+    }
+
+    /// Deletes whole lines, from `min_cursor` to `max_cursor`, with the
+    /// newline that separates them from the rest of the buffer.
+    ///
+    /// The register only receives the lines, without that newline.
+    fn delete_lines(
+        &mut self,
+        min_cursor: usize,
+        max_cursor: usize,
+        reg: Option<char>,
+    ) -> bool {
+        #[expect(clippy::string_slice, reason = "non-ascii not yet supported")]
+        self.registers.lock().insert(
+            &self.content[min_cursor..max_cursor],
+            true,
+            RegisterType::Linewise,
+            reg,
+        );
+        if max_cursor < self.len() {
+            self.remove(min_cursor, max_cursor.saturating_add(1));
+        } else {
+            self.remove(min_cursor.saturating_sub(1), max_cursor);
+        }
+        self.cursor.set(self.line_start(self.as_cursor()));
         true
     }
 
@@ -46,31 +68,25 @@ impl Buffer {
     /// It can include the bounds (meaning the delimiters like `(` or `}` will
     /// be included in the operator) or excluded.
     #[expect(clippy::arithmetic_side_effects, reason = "smaller than len")]
-    fn get_delimitation_indices(
+    pub(super) fn get_delimitation_indices(
         &mut self,
         delimitation: Delimitation,
         include_bounds: bool,
         repetition: usize,
     ) -> Option<(usize, usize)> {
         let (min, max) = match delimitation {
-            Delimitation::Group(open, close) if include_bounds => self
-                .get_delimitation_indices_fn(
-                    |ch| ch == open,
-                    |ch| ch == close,
-                    false,
-                )
-                .map(|(start, end)| {
+            Delimitation::Group(open, close) =>
+                self.get_group_indices(open, close, include_bounds),
+            Delimitation::Quote(quote) if include_bounds =>
+                self.get_quote_indices(quote).map(|(start, end)| {
                     (
-                        start.saturating_sub(1),
-                        if end == self.len() { end } else { end + 1 },
+                        start.saturating_sub(quote.len_utf8()),
+                        end.saturating_add(quote.len_utf8()),
                     )
                 }),
-            Delimitation::Group(open, close) => self
-                .get_delimitation_indices_fn(
-                    |ch| ch == open,
-                    |ch| ch == close,
-                    false,
-                ),
+            Delimitation::Quote(quote) => self.get_quote_indices(quote),
+            Delimitation::Paragraph =>
+                return self.get_paragraph_indices(include_bounds, repetition),
             Delimitation::Word => {
                 let cursor = IsIdentChar::new(self.as_char()?);
                 let good = |ch| cursor.xor(ch);
@@ -108,9 +124,11 @@ impl Buffer {
 
         let at_end = self.as_cursor() == self.len();
         let maybe_start = if at_end || !is_start(self.as_char()?) {
-            before.find(|ch| is_start(ch.1)).map(|ch| ch.0 + 1)
+            before
+                .find(|ch| is_start(ch.1))
+                .map(|(idx, ch)| idx + ch.len_utf8())
         } else {
-            Some(self.as_cursor() + 1)
+            Some(self.as_cursor() + self.as_char()?.len_utf8())
         };
         let maybe_end = after.find(|(_, ch)| is_end(*ch)).map(|(idx, _)| idx);
 
@@ -126,17 +144,37 @@ impl Buffer {
             (None | Some(_), None) => None,
             (None, Some(end)) =>
             // PERF: iterating for the second time
-                if let Some((start, _)) =
+                if let Some((start, ch)) =
                     self.chars_after_cursor().find(|(_, ch)| is_start(*ch))
                     && start <= end
                 {
-                    Some((start + 1, end))
+                    Some((start + ch.len_utf8(), end))
                 } else {
                     None
                 },
         }
     }
 
+    /// Returns the indices that bound the group around the cursor, like `i(`,
+    /// including the brackets if `include_bounds` is `true`.
+    fn get_group_indices(
+        &self,
+        open: char,
+        close: char,
+        include_bounds: bool,
+    ) -> Option<(usize, usize)> {
+        let (start, end) = self.get_delimitation_indices_fn(
+            |ch| ch == open,
+            |ch| ch == close,
+            false,
+        )?;
+        Some(if include_bounds {
+            (start.saturating_sub(1), end.saturating_add(1).min(self.len()))
+        } else {
+            (start, end)
+        })
+    }
+
     /// Get the cursor indices that describe the part of the buffer to be edited
     /// by the motion of an operator.
     fn get_motion_delimination_indices(
@@ -163,64 +201,147 @@ impl Buffer {
         }
     }
 
-    /// Updates the buffer with an [`Operator`] action.
-    pub(super) fn update_with_operator(
+    /// Get the cursor indices that describe the part of the buffer to be edited
+    /// by an operator, applied `num` times on the scope.
+    pub(super) fn get_operator_indices(
         &mut self,
-        op: Operator,
         scope: OperatorScope,
         num: usize,
-        reg: Option<char>,
-    ) -> bool {
+    ) -> Option<(usize, usize)> {
         let mut min = self.len();
         let mut max = 0;
         for _ in 0..num {
             let Some((this_min, this_max)) = (match scope {
-                OperatorScope::WholeLine => Some((0, self.len())),
+                OperatorScope::WholeLine => Some((
+                    self.line_start(self.as_cursor()),
+                    self.line_end(self.as_cursor()),
+                )),
                 OperatorScope::Goto(first, second) =>
                     self.get_motion_delimination_indices(first, second),
                 OperatorScope::Inner(delim, count) =>
                     self.get_delimitation_indices(delim, false, count),
                 OperatorScope::Around(delim, count) =>
                     self.get_delimitation_indices(delim, true, count),
+                OperatorScope::Custom(id) => self.get_custom_object_indices(id),
             }) else {
-                return false;
+                return None;
             };
             min = min.min(this_min);
             max = max.max(this_max);
-            self.cursor.set(max);
+            self.cursor.set(if scope.is_linewise() {
+                max.saturating_add(1)
+            } else {
+                max
+            });
         }
+        Some((min, max))
+    }
+
+    /// Returns the indices that bound the text between two quotes of the
+    /// current line, excluding the quotes.
+    ///
+    /// Quotes are paired from the beginning of the line. If the cursor isn't
+    /// between two quotes, the next quoted text of the line is used, like in
+    /// vim.
+    fn get_quote_indices(&self, quote: char) -> Option<(usize, usize)> {
+        let cursor = self.as_cursor();
+        let line_start = self.line_start(cursor);
+        let quotes = self
+            .content
+            .get(line_start..self.line_end(cursor))?
+            .match_indices(quote)
+            .map(|(idx, _)| line_start.saturating_add(idx))
+            .collect::<Vec<_>>();
+        let before = quotes.iter().take_while(|idx| **idx < cursor).count();
+        let opening = if before.is_multiple_of(2) {
+            before
+        } else {
+            before.saturating_sub(1)
+        };
+        Some((
+            quotes.get(opening)?.saturating_add(quote.len_utf8()),
+            *quotes.get(opening.saturating_add(1))?,
+        ))
+    }
+
+    /// Removes the part of the buffer between the two cursors, and places the
+    /// cursor at the beginning of the removed part.
+    fn remove(&mut self, min_cursor: usize, max_cursor: usize) {
+        let old_content = take(&mut self.content);
+        self.content.reserve(old_content.len());
+        #[expect(clippy::string_slice, reason = "non-ascii not yet supported")]
+        // TODO: add support for UTF-8
+        {
+            self.content.push_str(&old_content[0..min_cursor]);
+            self.content.push_str(&old_content[max_cursor..]);
+        };
+        self.cursor = BoundedUsize::with_capacity(self.content.len());
+        self.cursor.set(min_cursor);
+    }
+
+    /// Updates the buffer with an [`Operator`] action.
+    pub(super) fn update_with_operator(
+        &mut self,
+        op: Operator,
+        scope: OperatorScope,
+        num: usize,
+        reg: Option<char>,
+    ) -> bool {
+        let cursor = self.as_cursor();
+        let Some((min, max)) = self.get_operator_indices(scope, num) else {
+            return false;
+        };
         self.cursor.set(min);
+        let ty = if scope.is_linewise() {
+            RegisterType::Linewise
+        } else {
+            RegisterType::Charwise
+        };
         let fun = match op {
-            Operator::Delete => return self.delete(min, max, reg),
-            Operator::Yank => {
-                #[expect(clippy::string_slice, reason = "utf8 not supported")]
-                self.registers.insert(&self.content[min..max], false, reg);
-                return true;
-            }
+            Operator::Delete if ty == RegisterType::Linewise =>
+                return self.delete_lines(min, max, reg),
+            Operator::Delete => return self.delete(min, max, ty, reg),
+            Operator::Yank => return self.yank(min, max, ty, reg),
             Operator::Change =>
-                return self.delete(min, max, reg) && {
-                    self.mode.switch_to(Mode::Insert);
+                return self.delete(min, max, ty, reg) && {
+                    self.select_mode(Mode::Insert);
                     true
                 },
-            Operator::Capitalise => char::to_ascii_uppercase,
-            Operator::LowerCase => char::to_ascii_lowercase,
+            Operator::Comment => return self.toggle_comments(min, max),
+            Operator::Custom(id) =>
+                return self.apply_custom_operator(id, min, max),
+            Operator::Dedent => return self.shift_lines(min, max, false),
+            Operator::Exchange => return self.exchange(min, max),
+            Operator::Filter => return self.start_filter(cursor, scope, num),
+            Operator::Format => return self.format_lines(min, max, None),
+            Operator::FormatKeepCursor =>
+                return self.format_lines(min, max, Some(cursor)),
+            Operator::Indent => return self.shift_lines(min, max, true),
+            // The surrounding is only known after the motion: see `ys`.
+            Operator::Surround => return false,
+            Operator::ReplaceWithRegister =>
+                return self.replace_with_register(min, max, reg),
+            Operator::Capitalise => upper_case,
+            Operator::LowerCase => lower_case,
+            Operator::Rot13 => rot13,
+            Operator::TitleCase => title_case,
             Operator::ToggleCase => toggle_case,
         };
-        self.apply(min, max, fun);
-        true
+        self.convert_case(min, max, fun)
     }
-}
 
-/// Toggles the case of a char: capitals will be lowered and lower case letters
-/// will be capitalised.
-#[expect(
-    clippy::trivially_copy_pass_by_ref,
-    reason = "align with to_ascii_{upper,lower}case"
-)]
-const fn toggle_case(ch: &char) -> char {
-    if ch.is_ascii_uppercase() {
-        ch.to_ascii_lowercase()
-    } else {
-        ch.to_ascii_uppercase()
+    /// Copies the text between `min` and `max` in a register, like `y`, and
+    /// sets `'[` and `']` to it.
+    fn yank(
+        &mut self,
+        min: usize,
+        max: usize,
+        ty: RegisterType,
+        reg: Option<char>,
+    ) -> bool {
+        #[expect(clippy::string_slice, reason = "utf8 not supported")]
+        self.registers.lock().insert(&self.content[min..max], false, ty, reg);
+        self.marks.set_change(&self.content, min, max);
+        true
     }
 }
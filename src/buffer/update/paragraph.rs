@@ -0,0 +1,72 @@
+use crate::Buffer;
+
+impl Buffer {
+    /// Returns the indices that bound `count` paragraphs from the cursor,
+    /// like `ip`, or `ap` if `around` is `true`.
+    ///
+    /// The bounds are the beginning of the first line and the end of the
+    /// last line. Blank lines between paragraphs count as paragraphs for
+    /// `ip`, and are included after each paragraph for `ap`, or before the
+    /// first one if there are none after the last one, or omitted if there
+    /// are none at all.
+    pub(super) fn get_paragraph_indices(
+        &self,
+        around: bool,
+        count: usize,
+    ) -> Option<(usize, usize)> {
+        let start = self.run_start(self.line_start(self.as_cursor()));
+        let runs = if around { count.saturating_mul(2) } else { count };
+        let mut end = self.run_end(start);
+        let mut missing = runs.saturating_sub(1);
+        while missing > 0 && end < self.len() {
+            end = self.run_end(end.saturating_add(1));
+            missing = missing.saturating_sub(1);
+        }
+        match missing {
+            0 => Some((start, end)),
+            _ if around && start > 0 => Some((
+                self.run_start(self.line_start(start.saturating_sub(1))),
+                end,
+            )),
+            1 if around => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the line that starts at `start` is empty or only
+    /// holds spaces.
+    fn is_blank_line(&self, start: usize) -> bool {
+        self.content
+            .get(start..self.line_end(start))
+            .is_some_and(|line| line.trim().is_empty())
+    }
+
+    /// Returns the end of the last line of the run of lines that starts at
+    /// `start`, that are all blank or all non-blank.
+    fn run_end(&self, start: usize) -> usize {
+        let blank = self.is_blank_line(start);
+        let mut end = self.line_end(start);
+        while end < self.len()
+            && self.is_blank_line(end.saturating_add(1)) == blank
+        {
+            end = self.line_end(end.saturating_add(1));
+        }
+        end
+    }
+
+    /// Returns the beginning of the first line of the run of lines that ends
+    /// with the line that starts at `start`, that are all blank or all
+    /// non-blank.
+    fn run_start(&self, start: usize) -> usize {
+        let blank = self.is_blank_line(start);
+        let mut first = start;
+        while first > 0 {
+            let previous = self.line_start(first.saturating_sub(1));
+            if self.is_blank_line(previous) != blank {
+                break;
+            }
+            first = previous;
+        }
+        first
+    }
+}
@@ -0,0 +1,256 @@
+use core::iter::repeat_n;
+
+use crate::Buffer;
+use crate::buffer::keymaps::Paste;
+use crate::buffer::registers::RegisterType;
+use crate::buffer::update::indent::indent_of;
+
+impl Buffer {
+    /// Replaces the indent of the first line of `content` by the indent of the
+    /// current line, and shifts the other lines by the same amount, like `]p`.
+    ///
+    /// Lines that are less indented than the first one lose their indent,
+    /// and empty lines stay empty.
+    fn indent_like_current_line(&self, content: &str) -> String {
+        let line_start = self.line_start(self.as_cursor());
+        let indent = indent_of(
+            self.content
+                .get(line_start..self.line_end(line_start))
+                .unwrap_or_default(),
+        );
+        let first_indent =
+            indent_of(content.split('\n').next().unwrap_or_default());
+        content
+            .split('\n')
+            .map(|line| {
+                let unindented = line
+                    .strip_prefix(first_indent)
+                    .unwrap_or_else(|| line.trim_start_matches([' ', '\t']));
+                if unindented.is_empty() {
+                    String::new()
+                } else {
+                    format!("{indent}{unindented}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Inserts the content of the register at the cursor, and moves the
+    /// cursor after it, like `<C-r>` in insert mode.
+    ///
+    /// Linewise content is inserted with its trailing newline.
+    pub(super) fn insert_register(&mut self, reg: char) -> bool {
+        let Some(text) =
+            self.registers.lock().get(Some(reg)).map(|(content, ty)| {
+                if ty == RegisterType::Linewise {
+                    format!("{content}\n")
+                } else {
+                    content.to_owned()
+                }
+            })
+        else {
+            return false;
+        };
+        self.content.insert_str(self.as_cursor(), &text);
+        self.inserted.push_str(&text);
+        self.cursor.set_max(self.len());
+        self.cursor.set(self.as_cursor().saturating_add(text.len()));
+        true
+    }
+
+    /// Returns the beginning of the line that follows the one containing `idx`.
+    ///
+    /// If there is no next line, an empty one is created at the end of the
+    /// buffer.
+    fn next_line_start_or_create(&mut self, idx: usize) -> usize {
+        let line_end = self.line_end(idx);
+        if line_end == self.len() {
+            self.content.push('\n');
+        }
+        line_end.saturating_add(1)
+    }
+
+    /// Pastes the content of the register `count` times, after the cursor or
+    /// before, according to `paste`.
+    ///
+    /// The content is pasted according to its [`RegisterType`]: inline for
+    /// charwise content, on new lines for linewise content and column by
+    /// column for blockwise content. The copies are inserted at once, and
+    /// the cursor is left just after them for `gp` and `gP`.
+    pub(super) fn paste(
+        &mut self,
+        paste: Paste,
+        count: usize,
+        reg: Option<char>,
+    ) -> bool {
+        let Some((content, ty)) = self
+            .registers
+            .lock()
+            .get(reg)
+            .map(|(content, ty)| (content.to_owned(), ty))
+        else {
+            return false;
+        };
+        let after = paste.is_after();
+        let pasted = match ty {
+            RegisterType::Blockwise =>
+                self.paste_blockwise(&content, after, count),
+            RegisterType::Charwise => repeated(&content, count, "")
+                .map(|copies| self.paste_charwise(&copies, after)),
+            RegisterType::Linewise => {
+                let lines = if matches!(
+                    paste,
+                    Paste::AfterIndent | Paste::BeforeIndent
+                ) {
+                    self.indent_like_current_line(&content)
+                } else {
+                    content
+                };
+                repeated(&lines, count, "\n")
+                    .map(|copies| self.paste_linewise(&copies, after))
+            }
+        };
+        let Some(end) = pasted else {
+            return false;
+        };
+        if matches!(paste, Paste::AfterSkip | Paste::BeforeSkip) {
+            self.cursor.set(end);
+        }
+        true
+    }
+
+    /// Pastes the lines of `content` at the same column, on successive lines.
+    ///
+    /// Each line is repeated `count` times, padded to the width of the block.
+    /// Lines are padded with spaces if they are too short, and new lines are
+    /// created if the buffer is too short.
+    ///
+    /// Returns the index just after the block on its last line, or `None` if
+    /// the copies are too long to be built, in which case nothing is pasted.
+    fn paste_blockwise(
+        &mut self,
+        content: &str,
+        after: bool,
+        count: usize,
+    ) -> Option<usize> {
+        let mut line_start = self.line_start(self.as_cursor());
+        let mut column = self.as_cursor().saturating_sub(line_start);
+        if after && self.as_cursor() < self.line_end(self.as_cursor()) {
+            column = column.saturating_add(1);
+        }
+        let top_left = line_start.saturating_add(column);
+        let width = content.split('\n').map(|line| line.chars().count()).max();
+        let rows = content
+            .split('\n')
+            .map(|line| {
+                let padding = width
+                    .unwrap_or_default()
+                    .saturating_sub(line.chars().count());
+                let padded = format!("{line}{}", " ".repeat(padding));
+                let mut copies =
+                    repeated(&padded, count.saturating_sub(1), "")?;
+                copies.push_str(line);
+                Some(copies)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut end = top_left;
+        for (idx, copies) in rows.iter().enumerate() {
+            if idx != 0 {
+                line_start = self.next_line_start_or_create(line_start);
+            }
+            let line_end = self.line_end(line_start);
+            let line_len = line_end.saturating_sub(line_start);
+            if line_len < column {
+                let padding = repeat_n(' ', column.saturating_sub(line_len));
+                self.content.insert_str(line_end, &padding.collect::<String>());
+            }
+            end = line_start.saturating_add(column);
+            self.content.insert_str(end, copies);
+            end = end.saturating_add(copies.len());
+        }
+        self.cursor.set_max(self.len());
+        self.cursor.set(top_left);
+        Some(end)
+    }
+
+    /// Pastes `content` inline, after or before the char under the cursor.
+    ///
+    /// The cursor is placed on the last pasted char, or on the first one if
+    /// `content` spans over multiple lines.
+    ///
+    /// Returns the index just after the pasted text.
+    #[expect(clippy::arithmetic_side_effects, reason = "smaller than len")]
+    fn paste_charwise(&mut self, content: &str, after: bool) -> usize {
+        let cursor = self.as_cursor();
+        let pos = if let Some(ch) =
+            self.content.get(cursor..).and_then(|rest| rest.chars().next())
+        {
+            if after { cursor + ch.len_utf8() } else { cursor }
+        } else if after {
+            self.len()
+        } else {
+            self.content
+                .chars()
+                .next_back()
+                .map_or(0, |last| self.len() - last.len_utf8())
+        };
+        self.content.insert_str(pos, content);
+        self.cursor.set_max(self.len());
+        let end = pos + content.len();
+        match content.chars().next_back() {
+            Some(last) if !content.contains('\n') =>
+                self.cursor.set(end - last.len_utf8()),
+            Some(_) => self.cursor.set(pos),
+            None => (),
+        }
+        end
+    }
+
+    /// Pastes `content` on a new line, below the current line if `after` is
+    /// `true`, and above it otherwise. The cursor is placed at the beginning
+    /// of the pasted line.
+    ///
+    /// Returns the beginning of the line that follows the pasted lines, or
+    /// of the last pasted line if there is none.
+    fn paste_linewise(&mut self, content: &str, after: bool) -> usize {
+        let pos = if after {
+            let end = self.line_end(self.as_cursor());
+            self.content.insert(end, '\n');
+            end.saturating_add(1)
+        } else {
+            let start = self.line_start(self.as_cursor());
+            self.content.insert(start, '\n');
+            start
+        };
+        self.content.insert_str(pos, content);
+        self.cursor.set_max(self.len());
+        self.cursor.set(pos);
+        let end = pos.saturating_add(content.len());
+        if end < self.len() {
+            end.saturating_add(1)
+        } else {
+            self.line_start(end)
+        }
+    }
+}
+
+/// Returns `count` copies of `text`, joined by `separator`.
+///
+/// Returns `None` if the result is too long to be allocated, like for
+/// `99999999999999999999p`, instead of panicking.
+fn repeated(text: &str, count: usize, separator: &str) -> Option<String> {
+    let len = text
+        .len()
+        .checked_mul(count)?
+        .checked_add(separator.len().checked_mul(count.saturating_sub(1))?)?;
+    let mut out = String::new();
+    out.try_reserve_exact(len).ok()?;
+    for idx in 0..count {
+        if idx != 0 {
+            out.push_str(separator);
+        }
+        out.push_str(text);
+    }
+    Some(out)
+}
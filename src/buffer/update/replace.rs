@@ -0,0 +1,63 @@
+use core::cmp::Reverse;
+
+use crate::Buffer;
+
+impl Buffer {
+    /// Marks the region between `min` and `max` for an exchange, or swaps it
+    /// with the region marked before, like vim-exchange's `cx`.
+    ///
+    /// If one region contains the other, the larger one is replaced by the
+    /// smaller one. Regions that only partially overlap can't be exchanged:
+    /// the marked region is forgotten and `false` is returned.
+    pub(super) fn exchange(&mut self, min: usize, max: usize) -> bool {
+        let Some(marked) = self.exchange.take() else {
+            self.exchange = Some((min, max));
+            return true;
+        };
+        let mut regions = [marked, (min, max)];
+        regions.sort_by_key(|&(start, end)| (start, Reverse(end)));
+        let [(first_start, first_end), (second_start, second_end)] = regions;
+        let (Some(first), Some(second)) = (
+            self.content.get(first_start..first_end).map(str::to_owned),
+            self.content.get(second_start..second_end).map(str::to_owned),
+        ) else {
+            return false;
+        };
+        if second_end <= first_end {
+            self.content.replace_range(first_start..first_end, &second);
+        } else if second_start < first_end {
+            return false;
+        } else {
+            self.content.replace_range(second_start..second_end, &first);
+            self.content.replace_range(first_start..first_end, &second);
+        }
+        self.cursor.set_max(self.len());
+        self.cursor.set(first_start);
+        true
+    }
+
+    /// Replaces the text between `min` and `max` by the content of a
+    /// register, without changing the registers, like `gr`.
+    pub(super) fn replace_with_register(
+        &mut self,
+        min: usize,
+        max: usize,
+        reg: Option<char>,
+    ) -> bool {
+        let Some(text) = self
+            .registers
+            .lock()
+            .get(reg)
+            .map(|(content, _)| content.to_owned())
+        else {
+            return false;
+        };
+        if self.content.get(min..max).is_none() {
+            return false;
+        }
+        self.content.replace_range(min..max, &text);
+        self.cursor.set_max(self.len());
+        self.cursor.set(min);
+        true
+    }
+}
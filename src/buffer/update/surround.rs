@@ -0,0 +1,172 @@
+use core::ops::Range;
+
+use crate::buffer::keymaps::{
+    Delimitation, OperatorScope, SurroundAction, Surrounding
+};
+use crate::buffer::mode::{BufferMode, Tag};
+use crate::{Buffer, Mode};
+
+impl Buffer {
+    /// Surrounds the text of the operator scope, like `ysiw)`.
+    ///
+    /// The whole line is surrounded from its first non-space char, like with
+    /// `yss`. The cursor is placed at the beginning of the added surrounding.
+    fn add_surround(
+        &mut self,
+        scope: OperatorScope,
+        count: usize,
+        new: Surrounding,
+    ) -> bool {
+        let Some((mut min, max)) = self.get_operator_indices(scope, count)
+        else {
+            return false;
+        };
+        let Some(text) = self.content.get(min..max) else {
+            return false;
+        };
+        if scope == OperatorScope::WholeLine {
+            min = max.saturating_sub(text.trim_start().len());
+        }
+        let (open, close) = self.as_surrounding_texts(new);
+        self.content.insert_str(max, &close);
+        self.content.insert_str(min, &open);
+        self.cursor.set_max(self.len());
+        self.cursor.set(min);
+        true
+    }
+
+    /// Returns the texts to insert before and after the surrounded text.
+    ///
+    /// The closing tag only holds the name of the tag, without its
+    /// attributes.
+    fn as_surrounding_texts(
+        &self,
+        surrounding: Surrounding,
+    ) -> (String, String) {
+        match surrounding {
+            Surrounding::Pair(open, close, true) =>
+                (format!("{open} "), format!(" {close}")),
+            Surrounding::Pair(open, close, false) =>
+                (open.to_string(), close.to_string()),
+            Surrounding::Tag => {
+                let name =
+                    self.last_tag.split_whitespace().next().unwrap_or_default();
+                (format!("<{}>", self.last_tag), format!("</{name}>"))
+            }
+        }
+    }
+
+    /// Replaces the surrounding `old` found around the cursor by `new`, or
+    /// deletes it if `new` is `None`, like `cs"'` and `ds"`.
+    ///
+    /// The cursor is placed at the beginning of the surrounding.
+    fn change_surround(
+        &mut self,
+        old: Surrounding,
+        new: Option<Surrounding>,
+    ) -> bool {
+        let Some([open, close]) = self.find_surrounding(old) else {
+            return false;
+        };
+        let (new_open, new_close) = new.map_or_else(
+            || (String::new(), String::new()),
+            |surrounding| self.as_surrounding_texts(surrounding),
+        );
+        let start = open.start;
+        self.content.replace_range(close, &new_close);
+        self.content.replace_range(open, &new_open);
+        self.cursor.set_max(self.len());
+        self.cursor.set(start);
+        true
+    }
+
+    /// Leaves the tag prompt, and forgets the typed tag.
+    fn end_tag(&mut self) {
+        self.tag = None;
+        if matches!(self.mode, BufferMode::Tag(_)) {
+            self.mode.switch_to(Mode::Normal);
+        }
+    }
+
+    /// Returns the ranges of the opening and closing parts of the surrounding
+    /// around the cursor.
+    ///
+    /// The spaces inside the surrounding are part of it if the surrounding
+    /// has inner spaces, like `( abc )` for `ds(`.
+    fn find_surrounding(
+        &mut self,
+        surrounding: Surrounding,
+    ) -> Option<[Range<usize>; 2]> {
+        let Surrounding::Pair(open, close, spaced) = surrounding else {
+            return None;
+        };
+        let delimitation = if open == close {
+            Delimitation::Quote(open)
+        } else {
+            Delimitation::Group(open, close)
+        };
+        let cursor = self.as_cursor();
+        let indices = self.get_delimitation_indices(delimitation, true, 1);
+        self.cursor.set(cursor);
+        let (start, end) = indices?;
+        let inner_start = start.saturating_add(open.len_utf8());
+        let inner_end = end.checked_sub(close.len_utf8())?;
+        let inner = self.content.get(inner_start..inner_end)?;
+        let (leading, trailing) = if spaced && !inner.trim().is_empty() {
+            (
+                inner.len().saturating_sub(inner.trim_start().len()),
+                inner.len().saturating_sub(inner.trim_end().len()),
+            )
+        } else {
+            (0, 0)
+        };
+        Some([
+            start..inner_start.saturating_add(leading),
+            inner_end.saturating_sub(trailing)..end,
+        ])
+    }
+
+    /// Opens the tag prompt for a surround command.
+    fn start_tag(&mut self, tag: Tag) {
+        self.mode = BufferMode::Tag(tag);
+        self.tag = Some(String::new());
+    }
+
+    /// Applies an action of the surround commands, or of their tag prompt.
+    pub(super) fn update_surround(&mut self, action: SurroundAction) -> bool {
+        match action {
+            SurroundAction::Add(scope, count, new) =>
+                return self.add_surround(scope, count, new),
+            SurroundAction::Change(old, new) =>
+                return self.change_surround(old, Some(new)),
+            SurroundAction::Delete(old) =>
+                return self.change_surround(old, None),
+            SurroundAction::StartTag(tag) => self.start_tag(tag),
+            SurroundAction::TagChar(ch) =>
+                return self.tag.as_mut().is_some_and(|typed| {
+                    typed.push(ch);
+                    true
+                }),
+            SurroundAction::TagBackspace => match &mut self.tag {
+                Some(typed) if !typed.is_empty() => {
+                    typed.pop();
+                }
+                Some(_) => self.end_tag(),
+                None => return false,
+            },
+            SurroundAction::CancelTag => self.end_tag(),
+            SurroundAction::EndTag => match self.tag.take() {
+                Some(typed) => {
+                    self.end_tag();
+                    if typed.is_empty() {
+                        return false;
+                    }
+                    self.last_tag = typed;
+                }
+                // Repeated with `.`: the last tag is used again.
+                None => return !self.last_tag.is_empty(),
+            },
+        }
+        true
+    }
+}
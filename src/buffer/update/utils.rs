@@ -4,42 +4,33 @@ use core::str::CharIndices;
 use crate::Buffer;
 
 impl Buffer {
-    /// Capitalise part of the buffer
-    pub(super) fn apply<F>(&mut self, start: usize, end: usize, apply: F)
-    where F: Fn(&char) -> char {
-        self.content = self
-            .as_content()
-            .char_indices()
-            .map(
-                |(idx, ch)| {
-                    if idx < start || idx >= end { ch } else { apply(&ch) }
-                },
-            )
-            .collect();
-    }
-
     /// Returns the char pointed by the cursor
     ///
     /// The cursor is always in bounds so this always returns `Some`, except if
     /// the buffer is empty.
     pub(super) fn as_char(&self) -> Option<char> {
         self.content
-            .chars()
-            .nth(self.as_cursor())
+            .get(self.as_cursor()..)
+            .and_then(|after| after.chars().next())
             .map_or_else(|| self.content.chars().next_back(), Some)
     }
 
-    /// Returns the index of the cursor, starting from the end of the
+    /// Returns the number of chars between the cursor and the end of the
     /// string.
-    #[expect(clippy::arithmetic_side_effects, reason = "cursor <= len")]
-    pub(super) const fn as_end_index(&self) -> usize {
-        self.len() - self.as_cursor()
+    pub(super) fn as_end_index(&self) -> usize {
+        self.content
+            .get(self.as_cursor()..)
+            .map_or(0, |after| after.chars().count())
     }
 
     /// Returns [`CharIndices`] iterator for all chars located after the
     /// cursor in the buffer.
     pub(super) fn chars_after_cursor(&self) -> Skip<CharIndices<'_>> {
-        self.as_content().char_indices().skip(self.as_cursor())
+        let before = self
+            .content
+            .get(..self.as_cursor())
+            .map_or(0, |before| before.chars().count());
+        self.as_content().char_indices().skip(before)
     }
 
     /// Returns [`CharIndices`] iterator for all chars located before the
@@ -47,4 +38,59 @@ impl Buffer {
     pub(super) fn chars_before_cursor_rev(&self) -> Skip<Rev<CharIndices<'_>>> {
         self.as_content().char_indices().rev().skip(self.as_end_index())
     }
+
+    /// Returns the index of the end of the line that contains `idx`, that is
+    /// the index of the next `\n`, or the length of the buffer on the last
+    /// line.
+    pub(super) fn line_end(&self, idx: usize) -> usize {
+        self.content
+            .get(idx..)
+            .and_then(|after| after.find('\n'))
+            .map_or_else(|| self.len(), |len| idx.saturating_add(len))
+    }
+
+    /// Returns the index of the beginning of the line that contains `idx`.
+    pub(super) fn line_start(&self, idx: usize) -> usize {
+        self.content
+            .get(..idx)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |newline| newline.saturating_add(1))
+    }
+
+    /// Returns the beginning of the line of `min` and the end of the line of
+    /// `max`, to apply a linewise operator between them.
+    ///
+    /// `max` is excluded, so a motion that ends at the beginning of a line
+    /// doesn't include that line.
+    pub(super) fn lines_between(
+        &self,
+        min: usize,
+        max: usize,
+    ) -> (usize, usize) {
+        (
+            self.line_start(min),
+            self.line_end(if max > min { max.saturating_sub(1) } else { min }),
+        )
+    }
+
+    /// Returns the index of the char after the one pointed by the cursor, or
+    /// the length of the buffer if the cursor is on the last char.
+    pub(super) fn next_char_index(&self) -> usize {
+        let cursor = self.as_cursor();
+        cursor.saturating_add(
+            self.content
+                .get(cursor..)
+                .and_then(|after| after.chars().next())
+                .map_or(0, char::len_utf8),
+        )
+    }
+
+    /// Returns the index of the char before the cursor, or `0` if the cursor
+    /// is on the first char.
+    pub(super) fn previous_char_index(&self) -> usize {
+        self.content
+            .get(..self.as_cursor())
+            .and_then(|before| before.char_indices().next_back())
+            .map_or(0, |(idx, _)| idx)
+    }
 }
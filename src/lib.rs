@@ -50,6 +50,8 @@
     allow(clippy::missing_panics_doc, reason = "tests may panic")
 )]
 
+extern crate alloc;
+
 /// Publicly interfaced buffer to handle vim keymap and modes
 mod buffer;
 /// Parser to convert a vim-like keymap string to a list of events
@@ -57,7 +59,9 @@ mod event_parser;
 /// Common logic and data structures used throughout the code base
 pub(crate) mod utils;
 
-pub use buffer::{Buffer, Mode};
+pub use buffer::{
+    Buffer, ClipboardOption, ClipboardProvider, ClipboardSelection, CommandFilter, CommandProvider, FilterError, FilterProvider, MemoryProvider, Mode, RegisterEntry, RegisterType, SharedRegisters, UndoFileError
+};
 pub use crossterm;
 pub use event_parser::{
     ChevronGroupError, EventParsingError, ModifiedKeyError, parse_events
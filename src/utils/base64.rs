@@ -0,0 +1,65 @@
+/// Alphabet of the standard base64 encoding
+const ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Number of bits in a byte
+const BYTE_BITS: u32 = 8;
+
+/// Number of bits encoded by each base64 char
+const SEXTET_BITS: u32 = 6;
+
+/// Encodes bytes with the standard base64 encoding, with padding.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded =
+        String::with_capacity(bytes.len().div_ceil(3).saturating_mul(4));
+    for chunk in bytes.chunks(3) {
+        let mut group = (0..3).fold(u32::MIN, |group, idx| {
+            (group << BYTE_BITS)
+                | chunk.get(idx).map_or(u32::MIN, |byte| u32::from(*byte))
+        });
+        let mut sextets = [u32::MIN; 4];
+        for sextet in sextets.iter_mut().rev() {
+            *sextet = group;
+            group >>= SEXTET_BITS;
+        }
+        for (idx, sextet) in sextets.into_iter().enumerate() {
+            if idx <= chunk.len() {
+                encoded.push(as_base64_char(sextet));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Returns the base64 char that represents the 6 lowest bits of `sextet`.
+#[expect(clippy::as_conversions, reason = "sextet < 64")]
+fn as_base64_char(sextet: u32) -> char {
+    ALPHABET.get((sextet & 0x3f) as usize).map_or('=', |byte| char::from(*byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::base64::encode;
+
+    #[test]
+    fn rfc_vectors() {
+        for (input, output) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode(input.as_bytes()), output);
+        }
+    }
+
+    #[test]
+    fn all_bits() {
+        assert_eq!(encode(&[0xff, 0xfe, 0xfd, 0x00]), "//79AA==");
+    }
+}
@@ -1,4 +1,6 @@
 /// Defines a bounded vec to store it on the stack.
 pub mod array;
+/// Encodes bytes in base64, to send them through terminal escape sequences.
+pub mod base64;
 /// Defines a bounded usize newtype, to safely increment, decrement a cursor.
 pub mod bounded_usize;
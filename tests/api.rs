@@ -7,7 +7,7 @@ fn do_nothing() {
     let mut buffer = Buffer::default();
     assert!(!do_evt!(buffer, Enter));
     assert!(do_evt!(buffer, 'i'));
-    assert!(!do_evt!(buffer, Enter));
+    assert!(!do_evt!(buffer, Home));
 }
 
 #[test]
@@ -52,3 +52,9 @@ fn sizes() {
     assert_eq!(buffer.len(), 5);
     assert_eq!(buffer.as_cursor(), 4);
 }
+
+#[test]
+fn buffer_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Buffer>();
+}
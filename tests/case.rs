@@ -0,0 +1,35 @@
+mod common;
+
+buffer_tests!(
+
+upper_case: "straße x", "gUiw" => ("STRASSE x", 0),
+upper_case_line: "élan vital", "gUU" => "ÉLAN VITAL",
+upper_case_grows: "a ß b", "fßgUl" => ("a SS b", 3),
+upper_case_count: "ab\nçd", "2gUU" => "AB\nÇD",
+
+lower_case: "ÉLAN", "guiw" => "élan",
+lower_case_greek: "ΑΒΓ Ab", "guu" => "αβγ ab",
+lower_case_grows: "İ", "guu" => "i\u{307}",
+
+toggle_case: "Élan x", "g~iw" => "éLAN x",
+toggle_case_grows: "aß", "~~" => ("ASS", 2),
+toggle_case_count: "ßab", "3~" => "SSAB",
+toggle_case_multibyte: "éa", "~" => ("Éa", 2),
+toggle_case_not_letters: "a-b", "3~" => "A-B",
+
+rot13: "Hello, World!", "g??" => "Uryyb, Jbeyq!",
+rot13_twice: "Hello, World!", "g??g??" => "Hello, World!",
+rot13_not_ascii: "né zA", "g?$" => "aé mN",
+
+title_case: "hello wORLD", "gzz" => "Hello World",
+title_case_words: "the élan-vital", "gz$" => "The Élan-Vital",
+title_case_inside_word: "abcd", "lgzl" => "abcd",
+title_case_sharp_s: "ßen x", "gziw" => "Ssen x",
+title_case_digraph: "ǆungla ǉ", "gz$" => "ǅungla ǈ",
+title_case_ligature: "ﬁne", "gzz" => "Fine",
+
+case_dot: "straße über", "gUiww." => "STRASSE ÜBER",
+case_undo: "straße", "gUiwu" => ("straße", 0),
+case_redo: "straße", "gUiwu<C-r>" => "STRASSE",
+
+);
@@ -0,0 +1,55 @@
+mod common;
+
+buffer_tests!(
+
+[set_commentstring("# %s")]
+
+gcc: "a\nb", "gcc" => ("# a\nb", 0),
+gcc_twice: "a\nb", "gccgcc" => "a\nb",
+gcc_count: "a\nb\nc", "2gcc" => "# a\n# b\nc",
+gcc_motion_count: "a\nb\nc", "gc2c" => "# a\n# b\nc",
+
+gc_motion: "a b\nc", "gcw" => "# a b\nc",
+gc_motion_lines [set_commentstring("// %s")]:
+    "a (b\nc) d\ne", "f(gc%" => "// a (b\n// c) d\ne",
+
+gcip: "a\nb\n\nc", "gcip" => "# a\n# b\n\nc",
+gcip_last_paragraph: "a\nb\n\nc", "fcgcip" => "a\nb\n\n# c",
+gcip_uncomment: "# a\n# b\n\nc", "gcip" => "a\nb\n\nc",
+
+gc_keeps_indent: "  a\n    b", "gcip" => ("  # a\n  #   b", 2),
+gc_keeps_indent_uncomment: "  # a\n  #   b", "gcip" => "  a\n    b",
+gc_keeps_tab_indent: "\ta", "gcc" => "\t# a",
+
+gc_mixed_lines: "# a\nb", "gcip" => "# # a\n# b",
+gc_mixed_lines_twice: "# a\nb", "gcipgcip" => "# a\nb",
+
+gc_blank_lines: "a\n\nb", "3gcc" => "# a\n\n# b",
+gc_blank_lines_uncomment: "# a\n  \n# b", "3gcc" => "a\n  \nb",
+gc_empty_comment: "#\n# a", "2gcc" => "\na",
+
+gc_html [set_commentstring("<!-- %s -->")]: "a", "gcc" => "<!-- a -->",
+gc_html_uncomment [set_commentstring("<!-- %s -->")]:
+    "<!-- a -->", "gcc" => "a",
+gc_html_uncomment_without_spaces [set_commentstring("<!-- %s -->")]:
+    "<!--a-->", "gcc" => "a",
+gc_uncomment_without_space [set_commentstring("// %s")]: "//a", "gcc" => "a",
+gc_commentstring_without_space [set_commentstring("--%s")]:
+    "a", "gcc" => "--a",
+gc_invalid_commentstring [set_commentstring("#")]: "a", "gcc" => "a",
+
+gc_dot: "a\nb", "gccfb." => "# a\n# b",
+gc_undo: "a\nb", "gcipu" => "a\nb",
+gc_redo: "a\nb", "gcipu<C-r>" => "# a\n# b",
+
+change_still_works: "abc def", "ciwx" => "x def",
+operator_still_works: "abc", "gUiw" => "ABC",
+
+);
+
+#[test]
+fn gc_default_commentstring() {
+    let mut buffer = Buffer::from("a");
+    buffer.update_from_string("gcc").unwrap();
+    assert_eq!(buffer.as_content(), "/* a */");
+}
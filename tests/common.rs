@@ -21,22 +21,111 @@ macro_rules! do_evt {
     };
 }
 
+/// Generates a test for each row of a table.
+///
+/// A row is `name: "keys" => expected,` or `name: "content", "keys" =>
+/// expected,` to start from a buffer holding `content`. The expected result
+/// is either the content of the buffer, a `("content", cursor)` pair, or a
+/// list of checks among `content`, `cursor`, `mode`, `mark 'x'` and
+/// `register 'x'`, like `{ cursor: 2, register 'a': "abc" }`.
+///
+/// Options are set with setter calls between brackets, like
+/// `[set_shiftwidth(2), set_expandtab(true)]`, either before the first row for
+/// all the rows, or after the name of a row for that row only.
 #[macro_export]
 macro_rules! buffer_tests {
-    ($($name:ident: $keymaps:literal => $output:literal,)*) => {
+    (@buffer $keys:literal) => {
+        Buffer::default()
+    };
+    (@buffer $content:literal $keys:literal) => {
+        Buffer::from($content)
+    };
+    (@check $buffer:ident, $keys:expr, $content:literal) => {
+        buffer_tests!(@field $buffer, $keys, content, $content);
+    };
+    (@check $buffer:ident, $keys:expr, ($content:literal, $cursor:expr)) => {
+        buffer_tests!(@field $buffer, $keys, content, $content);
+        buffer_tests!(@field $buffer, $keys, cursor, $cursor);
+    };
+    (@check $buffer:ident, $keys:expr, {
+        $($field:ident $($name:literal)?: $value:expr),* $(,)?
+    }) => {
+        $(buffer_tests!(@field $buffer, $keys, $field $($name)?, $value);)*
+    };
+    (@field $buffer:ident, $keys:expr, content, $value:expr) => {
+        assert_eq!(
+            $buffer.as_content(),
+            $value,
+            "Keys: \x1b[35m{}\x1b[0m",
+            $keys
+        );
+    };
+    (@field $buffer:ident, $keys:expr, cursor, $value:expr) => {
+        assert_eq!(
+            $buffer.as_cursor(),
+            $value,
+            "Cursor after keys: \x1b[35m{}\x1b[0m",
+            $keys
+        );
+    };
+    (@field $buffer:ident, $keys:expr, mark $name:literal, $value:expr) => {
+        assert_eq!(
+            $buffer.as_mark($name),
+            $value,
+            "Mark {} after keys: \x1b[35m{}\x1b[0m",
+            $name,
+            $keys
+        );
+    };
+    (@field $buffer:ident, $keys:expr, mode, $value:expr) => {
+        assert_eq!(
+            $buffer.as_mode(),
+            $value,
+            "Mode after keys: \x1b[35m{}\x1b[0m",
+            $keys
+        );
+    };
+    (@field $buffer:ident, $keys:expr, register $name:literal, $value:expr) => {
+        assert_eq!(
+            $buffer.as_register($name).unwrap_or_default().0,
+            $value,
+            "Register {} after keys: \x1b[35m{}\x1b[0m",
+            $name,
+            $keys
+        );
+    };
+    (@keys $keys:literal) => {
+        $keys
+    };
+    (@keys $content:literal $keys:literal) => {
+        $keys
+    };
+    (@set $buffer:ident [
+        $($setter:ident($($arg:expr),*) $(.$then:ident())*),* $(,)?
+    ]) => {
+        $($buffer.$setter($($arg),*)$(.$then())*;)*
+    };
+    (@tests $options:tt $(
+        $name:ident $([$($row_options:tt)*])?:
+            $first:literal $(, $keys:literal)? => $expected:tt,
+    )*) => {
             use vim_buffer::Buffer;
             $(
                 #[test]
                 fn $name() {
-                    let mut buffer = Buffer::default();
-                    buffer.update_from_string($keymaps).unwrap();
-                    assert_eq!(
-                        buffer.as_content(),
-                        $output,
-                        "Keys: \x1b[35m{}\x1b[0m",
-                        $keymaps
-                    );
+                    let mut buffer = buffer_tests!(@buffer $first $($keys)?);
+                    buffer_tests!(@set buffer $options);
+                    buffer_tests!(@set buffer [$($($row_options)*)?]);
+                    let keys = buffer_tests!(@keys $first $($keys)?);
+                    buffer.update_from_string(keys).unwrap();
+                    buffer_tests!(@check buffer, keys, $expected);
                 }
             )*
     };
+    ([$($options:tt)*] $($rows:tt)*) => {
+        buffer_tests!(@tests [$($options)*] $($rows)*);
+    };
+    ($($rows:tt)*) => {
+        buffer_tests!(@tests [] $($rows)*);
+    };
 }
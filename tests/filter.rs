@@ -0,0 +1,142 @@
+use vim_buffer::{Buffer, CommandFilter, FilterError, Mode};
+
+/// Returns a buffer holding `content`, with a filter that knows `upper`,
+/// `dup` and `sort`, and fails with any other command.
+fn buffer_with_filter(content: &str) -> Buffer {
+    let mut buffer = Buffer::from(content);
+    buffer.set_filter(|command: &str, input: &str| match command {
+        "upper" => Ok(input.to_uppercase()),
+        "dup" => Ok(format!("{input}{input}")),
+        "sort" => {
+            let mut lines = input.lines().collect::<Vec<_>>();
+            lines.sort_unstable();
+            Ok(lines.join("\n"))
+        }
+        _ => Err(FilterError::Message(format!("{command}: not found"))),
+    });
+    buffer
+}
+
+#[test]
+fn filter_line() {
+    let mut buffer = buffer_with_filter("abc\ndef");
+    buffer.update_from_string("!!upper<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "ABC\ndef");
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+    assert_eq!(buffer.as_cursor(), 0);
+}
+
+#[test]
+fn filter_count() {
+    let mut buffer = buffer_with_filter("c\nb\na\nd");
+    buffer.update_from_string("3!!sort<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "a\nb\nc\nd");
+
+    let mut buffer = buffer_with_filter("c\nb\na\nd");
+    buffer.update_from_string("!3!sort<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "a\nb\nc\nd");
+}
+
+#[test]
+fn filter_motion() {
+    let mut buffer = buffer_with_filter("x (b\na) y\nz");
+    buffer.update_from_string("f(!%upper<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "X (B\nA) Y\nz");
+    assert_eq!(buffer.as_cursor(), 0);
+}
+
+#[test]
+fn filter_prompt() {
+    let mut buffer = buffer_with_filter("abc");
+    buffer.update_from_string("!!uppx").unwrap();
+    assert_eq!(buffer.as_filter_command(), Some("uppx"));
+    buffer.update_from_string("<BS>er<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "ABC");
+    assert_eq!(buffer.as_filter_command(), None);
+}
+
+#[test]
+fn filter_cancel() {
+    let mut buffer = buffer_with_filter("abc");
+    buffer.update_from_string("!!upper<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+    assert_eq!(buffer.as_filter_command(), None);
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+    buffer.update_from_string(".x").unwrap();
+    assert_eq!(buffer.as_content(), "bc");
+}
+
+#[test]
+fn filter_empty_command() {
+    let mut buffer = buffer_with_filter("abc");
+    buffer.update_from_string("!!<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+}
+
+#[test]
+fn filter_error() {
+    let mut buffer = buffer_with_filter("abc");
+    buffer.update_from_string("$!!nope<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+    assert_eq!(buffer.as_cursor(), 3);
+    assert!(matches!(
+        buffer.take_filter_error(),
+        Some(FilterError::Message(message)) if message == "nope: not found"
+    ));
+    assert!(buffer.take_filter_error().is_none());
+}
+
+#[test]
+fn filter_no_filter() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string("!!sort<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+    assert!(matches!(buffer.take_filter_error(), Some(FilterError::NoFilter)));
+}
+
+#[test]
+fn filter_undo() {
+    let mut buffer = buffer_with_filter("c\nb\na");
+    buffer.update_from_string("3!!sort<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "a\nb\nc");
+    buffer.update_from_string("u").unwrap();
+    assert_eq!(buffer.as_content(), "c\nb\na");
+    buffer.update_from_string("<C-r>").unwrap();
+    assert_eq!(buffer.as_content(), "a\nb\nc");
+}
+
+#[test]
+fn filter_dot() {
+    let mut buffer = buffer_with_filter("a\nb");
+    buffer.update_from_string("!!dup<CR>.").unwrap();
+    assert_eq!(buffer.as_content(), "a\na\na\nb");
+}
+
+#[cfg(unix)]
+#[test]
+fn command_filter() {
+    let mut buffer = Buffer::from("c\nb\na\nd");
+    buffer.set_filter(CommandFilter::default());
+    buffer.update_from_string("3!!sort<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "a\nb\nc\nd");
+    buffer.update_from_string("!!tr a-z A-Z<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "A\nb\nc\nd");
+}
+
+#[cfg(unix)]
+#[test]
+fn command_filter_failure() {
+    let mut buffer = Buffer::from("abc");
+    buffer.set_filter(CommandFilter::new("sh"));
+    buffer.update_from_string("!!echo oops <gt>&2; exit 3<CR>").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+    assert!(matches!(
+        buffer.take_filter_error(),
+        Some(FilterError::Exit(Some(3), stderr)) if stderr == "oops"
+    ));
+
+    buffer.set_filter(CommandFilter::new("/nonexistent/shell"));
+    buffer.update_from_string("!!sort<CR>").unwrap();
+    assert!(matches!(buffer.take_filter_error(), Some(FilterError::Io(_))));
+}
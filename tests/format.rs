@@ -0,0 +1,67 @@
+mod common;
+
+buffer_tests!(
+
+gqq_short_line: "iabc def<Esc>gqq" => "abc def",
+gqq_spaces: "iabc   def<Esc>gqq" => "abc def",
+gww_short_line: "iabc   def<Esc>gww" => "abc def",
+
+gqq_wraps [set_textwidth(10)]:
+    "one two three four five", "gqq" => ("one two\nthree four\nfive", 19),
+gq_joins_lines [set_textwidth(20)]:
+    "one\ntwo\nthree four\nfive", "3gqq" => "one two three four\nfive",
+gq_keeps_paragraphs [set_textwidth(9)]:
+    "one two three\n\nfour five six", "3gqq" =>
+        "one two\nthree\n\nfour five\nsix",
+gq_keeps_indent [set_textwidth(10)]:
+    "  one two three", "gqq" => ("  one two\n  three", 12),
+gq_other_indent_other_paragraph [set_textwidth(20)]:
+    "  one\n    two", "2gqq" => "  one\n    two",
+gq_keeps_comment_leader [set_textwidth(13)]:
+    "// one two three\n// four", "2gqq" => "// one two\n// three four",
+gq_keeps_indented_comment_leader [set_textwidth(14)]:
+    "    # one two three", "gqq" => "    # one two\n    # three",
+gq_keeps_empty_comment_line [set_textwidth(20)]:
+    "// one two\n//\n// three", "3gqq" => "// one two\n//\n// three",
+gq_long_word [set_textwidth(5)]:
+    "a abcdefghijkl b", "gqq" => "a\nabcdefghijkl\nb",
+gq_motion [set_textwidth(12)]:
+    "f(one two\nthree) four five six", "f(gq%" =>
+        "f(one two\nthree) four\nfive six",
+gq_undo [set_textwidth(8)]:
+    "one two three", "gqqu" => "one two three",
+gq_dot [set_textwidth(8)]:
+    "one two three\n\nfour five six", "gqq." =>
+        "one two\nthree\n\nfour five six",
+gww_keeps_cursor_end [set_textwidth(10)]:
+    "one two three four", "$bgww" => ("one two\nthree four", 14),
+gww_keeps_cursor [set_textwidth(10)]:
+    "one two three four", "wgww" => ("one two\nthree four", 4),
+
+auto_wrap [set_textwidth(10)]:
+    "ione two three" => ("one two\nthree", 13),
+auto_wrap_indent [set_textwidth(10)]:
+    "i  one two three four" => "  one two\n  three\n  four",
+auto_wrap_comment [set_textwidth(10)]:
+    "i# one two three" => "# one two\n# three",
+auto_wrap_single_word [set_textwidth(10)]:
+    "iabcdefghijkl" => "abcdefghijkl",
+auto_wrap_without_textwidth [set_textwidth(0)]:
+    "ione two three" => "one two three",
+auto_wrap_formatoptions_text [set_textwidth(10), set_formatoptions("t")]:
+    "i# one two three<Esc>" => "# one two three",
+auto_wrap_formatoptions_comments [set_textwidth(10), set_formatoptions("c")]:
+    "ione two three<Esc>" => "one two three",
+
+);
+
+#[test]
+fn gq_default_width() {
+    let words = ["word"; 20].join(" ");
+    let mut buffer = Buffer::from(words);
+    buffer.set_textwidth(0);
+    buffer.update_from_string("gqq").unwrap();
+    let lens =
+        buffer.as_content().split('\n').map(str::len).collect::<Vec<_>>();
+    assert_eq!(lens, [79, 19]);
+}
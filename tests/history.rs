@@ -1,4 +1,4 @@
-use vim_buffer::Buffer;
+use vim_buffer::{Buffer, EventParsingError, Mode, UndoFileError};
 
 mod common;
 
@@ -105,3 +105,170 @@ fn undo_redo() {
     buffer.update_from_string("<C-r>").unwrap();
     assert_eq!(buffer.as_content(), "abcdefghi");
 }
+
+#[test]
+fn transaction_single_undo() {
+    let mut buffer = Buffer::default();
+    buffer
+        .transaction(|buf| {
+            buf.update_from_string("iabc<Esc>")?;
+            do_evt!(buf, 'x');
+            buf.update_from_string("adef<Esc>")
+        })
+        .unwrap();
+    assert_eq!(buffer.as_content(), "abdef");
+    do_evt!(buffer, 'u');
+    assert_eq!(buffer.as_content(), "");
+    buffer.update_from_string("<C-r>").unwrap();
+    assert_eq!(buffer.as_content(), "abdef");
+}
+
+#[test]
+fn transaction_rollback() {
+    let mut buffer = Buffer::from("abc def");
+    buffer.update_from_string("dw").unwrap();
+    let res: Result<(), _> = buffer.transaction(|buf| {
+        buf.update_from_string("xiz")?;
+        Err(EventParsingError::InvalidNamedKey)
+    });
+    assert_eq!(res, Err(EventParsingError::InvalidNamedKey));
+    assert_eq!(buffer.as_content(), "def");
+    assert_eq!(buffer.as_cursor(), 0);
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+    do_evt!(buffer, 'u');
+    assert_eq!(buffer.as_content(), "abc def");
+}
+
+#[test]
+fn transaction_rollback_state() {
+    let mut buffer = Buffer::from("abc def ghi");
+    buffer.update_from_string("wiXY<Esc>").unwrap();
+    let marks = ['[', ']', '.', '^'].map(|name| buffer.as_mark(name));
+    let registers =
+        ['"', '-', '.', '1', 'a'].map(|reg| buffer.as_register(reg));
+    let res: Result<(), _> = buffer.transaction(|buf| {
+        buf.update_from_string("dw\"ayiwddwiQ<Esc>")?;
+        Err(EventParsingError::InvalidNamedKey)
+    });
+    assert!(res.is_err());
+    assert_eq!(['[', ']', '.', '^'].map(|name| buffer.as_mark(name)), marks);
+    assert_eq!(
+        ['"', '-', '.', '1', 'a'].map(|reg| buffer.as_register(reg)),
+        registers
+    );
+    buffer.update_from_string("0.").unwrap();
+    assert_eq!(buffer.as_content(), "XYabc XYdef ghi");
+}
+
+#[test]
+fn transaction_rollback_insert() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string("Ade").unwrap();
+    let res: Result<(), ()> = buffer.transaction(|buf| {
+        buf.update_from_string("fg<Esc>").unwrap();
+        Err(())
+    });
+    assert!(res.is_err());
+    assert_eq!(buffer.as_mode(), Mode::Insert);
+    buffer.update_from_string("h<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "abcdeh");
+    assert_eq!(buffer.as_register('.').unwrap().0, "deh");
+}
+
+#[test]
+fn transaction_nested() {
+    let mut buffer = Buffer::default();
+    buffer
+        .transaction(|buf| {
+            buf.update_from_string("iabc<Esc>")?;
+            let inner: Result<(), ()> = buf.transaction(|inner_buf| {
+                inner_buf.update_from_string("adef<Esc>").unwrap();
+                Err(())
+            });
+            assert!(inner.is_err());
+            buf.transaction(|inner_buf| {
+                inner_buf.update_from_string("aghi<Esc>")
+            })
+        })
+        .unwrap();
+    assert_eq!(buffer.as_content(), "abcghi");
+    do_evt!(buffer, 'u');
+    assert_eq!(buffer.as_content(), "");
+}
+
+fn undo_file(buffer: &Buffer) -> String {
+    let mut file = vec![];
+    buffer.write_undo_file(&mut file).unwrap();
+    String::from_utf8(file).unwrap()
+}
+
+#[test]
+fn undo_file_roundtrip() {
+    let mut buffer = Buffer::default();
+    buffer.update_from_string("iabc<Esc>adef<Esc>aghi<Esc>u").unwrap();
+    let file = undo_file(&buffer);
+    assert_eq!(
+        file,
+        "vim-buffer undofile\nversion 1\nhash d80bda3fbe244a0a\ncursor \
+         2\nentries 4\nentry 0\n\nentry 3\nabc\nentry 6\nabcdef\nentry \
+         9\nabcdefghi\n"
+    );
+
+    let mut reopened = Buffer::from("abcdef");
+    reopened.read_undo_file(file.as_bytes()).unwrap();
+    reopened.update_from_string("<C-r>").unwrap();
+    assert_eq!(reopened.as_content(), "abcdefghi");
+    reopened.update_from_string("uuu").unwrap();
+    assert_eq!(reopened.as_content(), "");
+}
+
+#[test]
+fn undo_file_multiline_entry() {
+    let mut buffer = Buffer::from("a\nb");
+    buffer.update_from_string("x").unwrap();
+    let file = undo_file(&buffer);
+    let mut reopened = Buffer::from("\nb");
+    reopened.read_undo_file(file.as_bytes()).unwrap();
+    reopened.update_from_string("u").unwrap();
+    assert_eq!(reopened.as_content(), "a\nb");
+}
+
+#[test]
+fn undo_file_invalid() {
+    let mut buffer = Buffer::from("abc");
+    let file = undo_file(&buffer);
+    for (invalid, expected) in [
+        ("vim undofile\n", "not an undo file"),
+        ("vim-buffer undofile\nversion 2\n", "unsupported undo file version 2"),
+        ("vim-buffer undofile\nversion one\n", "malformed undo file"),
+        (
+            "vim-buffer undofile\nversion 1\nhash 0\n",
+            "undo file was written for a different content",
+        ),
+        (
+            &file.replace("cursor 0", "cursor 3"),
+            "history position out of bounds",
+        ),
+        (&file.replace("entry 3", "entry 4"), "undo file is truncated"),
+        (&format!("{file}entry 0\n\n"), "malformed undo file"),
+        (&file[..file.len() - 1], "undo file is truncated"),
+        (
+            &file.replace("entries 1", "entries 18446744073709551615"),
+            "undo file is truncated",
+        ),
+        (
+            &file.replace("entries 1", "entries 18446744073709551616"),
+            "malformed undo file",
+        ),
+    ] {
+        let err = buffer.read_undo_file(invalid.as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), expected, "{invalid:?}");
+    }
+    buffer.update_from_string("x").unwrap();
+    assert!(matches!(
+        buffer.read_undo_file(file.as_bytes()),
+        Err(UndoFileError::HashMismatch)
+    ));
+    buffer.update_from_string("u").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+}
@@ -0,0 +1,59 @@
+mod common;
+
+buffer_tests!(
+
+indent_line: "iabc<Esc><gt><gt>" => "\tabc",
+dedent_line: "i\t\tabc<Esc><lt><lt>" => "\tabc",
+dedent_no_indent: "iabc<Esc><lt><lt>" => "abc",
+dedent_spaces: "i   abc<Esc><lt><lt>" => "abc",
+indent_dot: "iabc<Esc><gt><gt>." => "\t\tabc",
+indent_undo: "iabc<Esc><gt><gt>u" => "abc",
+insert_ctrl_t: "iabc<C-t>d" => "\tabcd",
+insert_ctrl_d: "i\t\tabc<C-d>d" => "\tabcd",
+insert_ctrl_t_empty: "i<C-t>abc" => "\tabc",
+insert_ctrl_t_dot: "iabc<C-t><Esc>." => "\t\tababcc",
+
+shift_expandtab [set_shiftwidth(4), set_expandtab(true)]:
+    "abc", "<gt><gt>" => ("    abc", 4),
+shift_expandtab_partial [set_shiftwidth(2), set_expandtab(true)]:
+    "  abc", "<gt><gt>" => ("    abc", 4),
+shift_expandtab_tab [set_shiftwidth(4), set_expandtab(true)]:
+    "\tabc", "<lt><lt>" => ("    abc", 4),
+
+shift_mixed_indent_dot [set_shiftwidth(4)]:
+    "abc", "<gt><gt>.." => "\t    abc",
+shift_mixed_indent_spaces [set_shiftwidth(4)]:
+    "\t  abc", "<gt><gt>" => "\t      abc",
+shift_mixed_indent_dedent [set_shiftwidth(4)]:
+    "\t\tabc", "<lt><lt>" => "\t    abc",
+
+shift_count [set_shiftwidth(2), set_expandtab(true)]:
+    "a\nb\nc\nd", "2<gt><gt>" => "  a\n  b\nc\nd",
+shift_count_middle [set_shiftwidth(2), set_expandtab(true)]:
+    "a\nb\nc\nd", "<gt>3<gt>" => "  a\n  b\n  c\nd",
+shift_count_dot [set_shiftwidth(2), set_expandtab(true)]:
+    "  a\n  b\n  c", "2<lt><lt>." => "a\nb\n  c",
+
+shift_motion [set_shiftwidth(2), set_expandtab(true)]:
+    "a (b\nc) d\ne", "f(<gt>%" => ("  a (b\n  c) d\ne", 2),
+shift_motion_word [set_shiftwidth(2), set_expandtab(true)]:
+    "abc def\nghi", "<gt>w" => "  abc def\nghi",
+shift_motion_exclusive_line_start [set_shiftwidth(2), set_expandtab(true)]:
+    "abc\nghi", "<gt>w" => "  abc\nghi",
+
+shift_skips_empty_lines [set_shiftwidth(2), set_expandtab(true)]:
+    "a\n\nb", "3<gt><gt>" => "  a\n\n  b",
+
+shift_insert_indent [set_shiftwidth(2), set_expandtab(true)]:
+    "  abc", "A<C-t>" => ("    abc", 7),
+shift_insert_dedent [set_shiftwidth(2), set_expandtab(true)]:
+    "    abc", "A<C-d>" => ("  abc", 5),
+shift_insert_dedent_start [set_shiftwidth(2), set_expandtab(true)]:
+    "    abc", "0i<C-d>" => ("  abc", 2),
+shift_insert_dedent_no_indent [set_shiftwidth(2), set_expandtab(true)]:
+    "abc", "A<C-d>" => ("abc", 3),
+
+shift_tabstop [set_tabstop(4), set_shiftwidth(0)]:
+    "abc", "<gt><gt><gt><gt>" => "\t\tabc",
+
+);
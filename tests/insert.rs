@@ -6,5 +6,16 @@ backspace: "ia<BS><BS>bcd<BS>" => "bc",
 arrows: "iabc<Left>d<Right>e" => "abdce",
 right_end: "iabc<Right>d" => "abcd",
 left_start: "iabc<Esc>I<Left>d" => "dabc",
+count_insert: "3ifoo<Esc>" => "foofoofoo",
+count_append: "iab<Esc>02ax<Esc>" => "axxb",
+count_append_end: "iab<Esc>02Axy<Esc>" => "abxyxy",
+count_insert_start: "i  ab<Esc>2I-<Esc>" => "  --ab",
+count_backspace: "3iab<BS>c<Esc>" => "acacac",
+count_not_left: "3ifoo" => "foo",
+count_dot: "2ia<Esc>." => "aaaa",
+count_dot_override: "2ia<Esc>3." => "aaaaa",
+count_dot_override_kept: "2ib<Esc>3.." => "bbbbbbbb",
+append_end_dot: "iab<Esc>A-<Esc>0." => "ab--",
+open_line_dot_override: "ia<Esc>ob<Esc>2." => "a\nb\nb\nb",
 
 );
@@ -0,0 +1,34 @@
+mod common;
+
+buffer_tests!(
+
+join: "ab\n  cd\nef", "J" => ("ab cd\nef", 2),
+join_tab: "ab\n\tcd", "lJ" => ("ab cd", 2),
+join_count_one: "ab\ncd", "1J" => ("ab cd", 2),
+
+join_after_space: "ab \ncd", "J" => ("ab cd", 3),
+join_closing_paren: "f(a\n  )", "J" => ("f(a)", 3),
+join_empty_line: "ab\n\ncd", "J" => ("ab\ncd", 2),
+join_blank_line: "ab\n   \ncd", "J" => ("ab\ncd", 2),
+join_to_empty_line: "\n  cd", "J" => ("cd", 0),
+
+join_count: "a\nb\nc\nd", "3J" => ("a b c\nd", 3),
+join_count_two: "a\nb\nc\nd", "2J" => ("a b\nc\nd", 1),
+join_count_too_big: "a\nb\nc", "10J" => ("a b c", 3),
+
+join_last_line: "a\nb", "fbJ" => ("a\nb", 2),
+join_single_line: "a", "J" => "a",
+join_empty: "", "J" => "",
+
+join_raw: "ab\n  cd", "gJ" => ("ab  cd", 2),
+join_raw_count: "a \n b\n c", "3gJ" => ("a  b c", 4),
+join_raw_empty_line: "a\n\nb", "gJ" => "a\nb",
+
+join_dot: "a\nb\nc\nd", "J." => "a b c\nd",
+join_count_dot: "a\nb\nc\nd\ne", "2J." => "a b c\nd\ne",
+join_count_three_dot: "a\nb\nc\nd\ne", "3J." => "a b c d e",
+join_raw_dot: "a\n b\n c", "gJ." => "a b c",
+join_undo: "a\n  b", "Ju" => "a\n  b",
+join_redo: "a\n  b", "Ju<C-r>" => "a b",
+
+);
@@ -0,0 +1,69 @@
+mod common;
+
+buffer_tests!(
+
+jump: "(a) (b)", "%" => { cursor: 2 },
+jump_back: "(a) (b)", "%<C-o>" => { cursor: 0 },
+jump_back_and_forth: "(a) (b)", "%<C-o><C-i>" => { cursor: 2 },
+jump_forth_tab: "(a) (b)", "%<C-o><Tab>" => { cursor: 2 },
+jump_back_twice_once: "(a) (b)", "%w%<C-o>" => { cursor: 4 },
+jump_back_twice: "(a) (b)", "%w%<C-o><C-o>" => { cursor: 0 },
+jump_back_count: "(a) (b)", "%w%2<C-o>" => { cursor: 0 },
+jump_back_count_forth: "(a) (b)", "%w%2<C-o><C-i>" => { cursor: 4 },
+jump_back_count_forth_count: "(a) (b)", "%w%2<C-o>2<C-i>" => { cursor: 6 },
+
+jump_back_empty: "(a)", "<C-o>" => { cursor: 0 },
+jump_forth_empty: "(a)", "<C-i>" => { cursor: 0 },
+jump_back_too_far: "(a) (b)", "%<C-o><C-o>" => { cursor: 0 },
+jump_forth_too_far: "(a) (b)", "%<C-o><C-i><C-i>" => { cursor: 2 },
+jump_forth_not_walking: "(a) (b)", "%w<C-i>" => { cursor: 4 },
+
+small_motion: "(a) (b)", "w<C-o>" => { cursor: 1 },
+find_motion: "(a) (b)", "fbx<C-o>" => { cursor: 5 },
+jump_from_inside_group: "(a) bc", "fb%<C-o>" => { cursor: 4 },
+jump_after_small_motions: "(a) (b)", "ww%<C-o>" => { cursor: 2 },
+
+jump_list_skips_duplicates: "(a)", "%%<C-o>" => { cursor: 2 },
+jump_list_skips_duplicates_twice: "(a)", "%%<C-o><C-o>" => { cursor: 2 },
+jump_list_skips_many_duplicates: "(a)", "%%%%<C-o><C-o>" => { cursor: 2 },
+
+jump_list_follows_deletions: "xyz (a) (b)", "f(%w%0dw<C-o>" => ("(a) (b)", 4),
+jump_list_follows_deletions_forth: "xyz (a) (b)", "f(%w%0dw<C-o><C-i>" =>
+    { cursor: 0 },
+jump_list_follows_deletion_before: "ab (c) d", "f(%0lx<C-o>" => { cursor: 2 },
+jump_list_deleted_position: "x ab (c) d", "f(%0wd2w$<C-o>" => { cursor: 2 },
+
+change_list_edits: "abc def ghi", "xwxwx0" => ("bc ef hi", 0),
+change_list: "abc def ghi", "xwxwx0g;" => { cursor: 6 },
+change_list_twice: "abc def ghi", "xwxwx0g;g;" => { cursor: 3 },
+change_list_count: "abc def ghi", "xwxwx02g;" => { cursor: 3 },
+change_list_count_oldest: "abc def ghi", "xwxwx03g;" => { cursor: 0 },
+change_list_newer: "abc def ghi", "xwxwx0$3g;g," => { cursor: 3 },
+change_list_newer_count: "abc def ghi", "xwxwx0$3g;2g," => { cursor: 6 },
+change_list_newer_too_far: "abc def ghi", "xwxwx0$3g;3g," => { cursor: 6 },
+
+change_list_empty_older: "abc", "g;" => { cursor: 0 },
+change_list_empty_newer: "abc", "g," => { cursor: 0 },
+change_list_older_too_far: "abc def", "wx0g;g;" => { cursor: 4 },
+change_list_newer_too_far_single: "abc def", "wx0g;g," => { cursor: 4 },
+
+change_list_follows_deletions: "abcdef", "fex0xg;" => ("bcdf", 3),
+change_list_merges_deletions: "abc def", "wx0dwg;g;" => { cursor: 0 },
+change_list_follows_insertions: "abc def", "wx0iz<Esc>$g;g;" => { cursor: 5 },
+
+change_list_append: "abc def", "Axyz<Esc>0g;" => { cursor: 9 },
+change_list_insert: "abc def", "ixyz<Esc>$g;" => { cursor: 2 },
+change_list_change: "abc def", "wcwxyz<Esc>0g;" => { cursor: 6 },
+
+change_list_not_repeated: "abc def", "wx0g;." => ("abc f", 4),
+jump_list_not_repeated: "(a) bc", "fbx0%<C-o>." => ("a) c", 0),
+
+);
+
+#[test]
+fn jump_list_is_bounded() {
+    let mut buffer = Buffer::from("() ".repeat(150));
+    let keys = "%w".repeat(150);
+    buffer.update_from_string(&format!("{keys}200<C-o>")).unwrap();
+    assert_eq!(buffer.as_cursor(), 3 * 51);
+}
@@ -0,0 +1,85 @@
+use vim_buffer::Mode;
+
+mod common;
+
+/// Operator mapped to `gs`, that reverses the text.
+fn reverse(text: &str) -> Option<String> {
+    Some(text.chars().rev().collect())
+}
+
+/// Motion mapped to `]d`, to the next digit.
+fn next_digit(content: &str, cursor: usize) -> Option<usize> {
+    content
+        .char_indices()
+        .find(|&(idx, ch)| idx > cursor && ch.is_ascii_digit())
+        .map(|(idx, _)| idx)
+}
+
+/// Text object mapped to `iu`, made of the chars around the cursor that
+/// aren't spaces.
+fn non_spaces(content: &str, cursor: usize) -> Option<(usize, usize)> {
+    let start = content.get(..cursor)?.rfind(' ').map_or(0, |space| space + 1);
+    let end = content
+        .get(cursor..)?
+        .find(' ')
+        .map_or(content.len(), |len| cursor + len);
+    Some((start, end))
+}
+
+buffer_tests!(
+
+[
+    map_operator("gs", reverse).unwrap(),
+    map_motion("]d", next_digit).unwrap(),
+    map_text_object("iu", non_spaces).unwrap(),
+]
+
+custom_operator_builtin_motion: "abc def", "gsiw" => ("cba def", 0),
+custom_operator_end_of_line: "abc def", "wgs$" => ("abc fed", 4),
+custom_operator_motion_count: "ab cd ef", "gs2e" => "dc ba ef",
+custom_operator_count: "ab cd ef", "2gse" => "dc ba ef",
+custom_operator_whole_line: "abc\ndef", "gss" => "cba\ndef",
+custom_operator_whole_line_count: "ab\ncd\nef", "2gss" => "dc\nba\nef",
+
+custom_motion: "a1 b2 c3", "]d" => ("a1 b2 c3", 1),
+custom_motion_count: "a1 b2 c3", "3]d" => ("a1 b2 c3", 7),
+custom_motion_delete: "a1 b2 c3", "d]d" => "1 b2 c3",
+custom_motion_delete_count: "a1 b2 c3", "d2]d" => "2 c3",
+custom_motion_custom_operator: "a1 b2 c3", "gs]d" => "a1 b2 c3",
+custom_motion_custom_operator_reverses: "ab1", "gs]d" => "ba1",
+custom_motion_fails: "abc", "]d" => ("abc", 0),
+custom_motion_fails_delete: "abc", "d]d" => "abc",
+
+custom_text_object: "a x.y/z b", "fydiu" => ("a  b", 2),
+custom_text_object_custom_operator: "a x.y/z b", "fygsiu" => "a z/y.x b",
+custom_text_object_change: "a x.y/z b", "fyciuw<Esc>" => "a w b",
+custom_text_object_alone: "a x.y/z b", "iu" => "ua x.y/z b",
+custom_text_object_register: "a x.y/z b", "fy\"ayiu" =>
+    { register 'a': "x.y/z" },
+custom_text_object_surround: "a x.y/z b", "fyysiu)" => "a (x.y/z) b",
+
+builtin_operator: "abc def", "gUiw" => "ABC def",
+builtin_text_object: "abc def", "diw" => " def",
+builtin_motion: "abc def", "wge" => ("abc def", 2),
+builtin_unsupported: "abc", "]p" => "abc",
+builtin_unsupported_after_custom: "abc", "gsx" => "abc",
+builtin_insert: "abc", "ix" => { content: "xabc", mode: Mode::Insert },
+
+custom_dot: "ab cd", "gsiww." => "ba dc",
+custom_motion_dot: "a1 b2 c3", "d]d." => "2 c3",
+custom_undo: "ab cd", "gsiwu" => "ab cd",
+custom_redo: "ab cd", "gsiwu<C-r>" => "ba cd",
+
+remap [map_operator("gs", |text: &str| Some(text.to_uppercase())).unwrap()]:
+    "abc", "gsiw" => "ABC",
+
+);
+
+#[test]
+fn operator_leaves_buffer_unchanged() {
+    let mut buffer = Buffer::from("abc");
+    buffer.map_operator("gs", |_: &str| None).unwrap();
+    assert!(buffer.map_operator("g>", |_: &str| None).is_err());
+    buffer.update_from_string("gsiw").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+}
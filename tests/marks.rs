@@ -0,0 +1,71 @@
+mod common;
+
+buffer_tests!(
+
+no_marks: "abc", "w" => {
+    mark '[': None,
+    mark ']': None,
+    mark '.': None,
+    mark '^': None,
+    mark '<': None,
+    mark '>': None,
+    mark 'a': None,
+},
+
+change_marks_delete: "abc def", "wdw" =>
+    { mark '[': Some(4), mark ']': Some(4) },
+change_marks_delete_char: "abc def", "x" =>
+    { mark '[': Some(0), mark ']': Some(0) },
+change_marks_append: "abc", "Adef<Esc>" =>
+    { mark '[': Some(3), mark ']': Some(5) },
+change_marks_backspace: "abc", "ixy<BS>z<Esc>" =>
+    { mark '[': Some(0), mark ']': Some(1) },
+change_marks_change: "abc def", "wcwxyz<Esc>" =>
+    { mark '[': Some(4), mark ']': Some(6) },
+change_marks_paste_line: "ab\ncd", "yyp" =>
+    { mark '[': Some(3), mark ']': Some(5) },
+change_marks_operator: "abc def", "gUiw" =>
+    { mark '[': Some(0), mark ']': Some(2) },
+change_marks_replace_multibyte: "abc", "ré" =>
+    { mark '[': Some(0), mark ']': Some(0) },
+change_marks_insert_multibyte: "é b", "xiàé<Esc>" =>
+    { mark '[': Some(0), mark ']': Some(2) },
+
+change_marks_yank_object: "abc def", "wyiw" =>
+    { mark '[': Some(4), mark ']': Some(6) },
+change_marks_yank_motion: "abc def", "yw" =>
+    { mark '[': Some(0), mark ']': Some(3) },
+change_marks_yank_after_edit: "abc def", "xwyiw" =>
+    { mark '[': Some(3), mark ']': Some(5) },
+
+change_marks_undo: "abc def", "wdwu" =>
+    { mark '[': Some(4), mark ']': Some(6) },
+change_marks_redo: "abc def", "wdwu<C-r>" =>
+    { mark '[': Some(4), mark ']': Some(4) },
+
+change_marks_follow_edits: "abc def", "wyiw0x" => { mark '[': Some(0) },
+change_marks_follow_black_hole_edits: "abc def", "wyiw0\"_dl" =>
+    { mark '[': Some(0) },
+
+last_change_mark: "abc def", "wx0" => { mark '.': Some(4) },
+last_change_mark_newest: "abc def", "wxbx" => { mark '.': Some(0) },
+last_change_mark_follows_edits: "abc def", "wx0x" => { mark '.': Some(0) },
+last_change_mark_insert: "abc def", "Axyz<Esc>0" => { mark '.': Some(9) },
+
+last_insert_mark: "abc", "ixy<Esc>" => { mark '^': Some(2) },
+last_insert_mark_append: "abc", "Axy<Esc>" => { mark '^': Some(5) },
+last_insert_mark_follows_edits: "abc", "Axy<Esc>0x" => { mark '^': Some(4) },
+last_insert_mark_replace: "abc", "Rxy<Esc>" => { mark '^': Some(2) },
+last_insert_mark_empty_insert: "abc", "li<Esc>" => { mark '^': Some(1) },
+last_insert_mark_count: "abc", "3ix<Esc>" => { mark '^': Some(3) },
+
+gi: "abc def", "wiXY<Esc>0giZ<Esc>" => ("abc XYZdef", 6),
+gi_append: "abc def", "Ax<Esc>0giy" => "abc defxy",
+gi_follows_edits: "abc def", "wix<Esc>0xgiy" => "bc xydef",
+gi_without_insert: "abc", "lgix" => "axbc",
+gi_count: "abc", "lix<Esc>$2giy<Esc>" => "axyybc",
+
+gv_without_visual_mode: "abc", "gv" =>
+    { cursor: 0, mark '<': None, mark '>': None },
+
+);
@@ -0,0 +1,41 @@
+mod common;
+
+buffer_tests!(
+
+open_below: "ab\ncd", "ox<Esc>" => ("ab\nx\ncd", 3),
+open_below_last_line: "ab", "ox<Esc>" => ("ab\nx", 3),
+open_below_insert: "ab\ncd", "fcox" => ("ab\ncd\nx", 7),
+open_below_empty: "", "ox" => "\nx",
+
+open_above: "ab", "Ox<Esc>" => ("x\nab", 0),
+open_above_insert: "ab\ncd", "fcOx" => ("ab\nx\ncd", 4),
+
+open_without_autoindent: "  ab", "ox" => "  ab\nx",
+open_autoindent [set_autoindent(true)]: "  ab", "ox" => ("  ab\n  x", 8),
+open_above_autoindent [set_autoindent(true)]:
+    "\tab\ncd", "Ox" => "\tx\n\tab\ncd",
+open_above_autoindent_next_line [set_autoindent(true)]:
+    "ab\n  cd", "fdOx" => "ab\n  x\n  cd",
+
+enter: "abcd", "lli<CR><Esc>" => "ab\ncd",
+enter_insert: "", "ia<CR>b" => ("a\nb", 3),
+enter_without_autoindent: "  abcd", "fci<CR>" => "  ab\ncd",
+enter_autoindent [set_autoindent(true)]:
+    "  abcd", "fci<CR>" => ("  ab\n  cd", 7),
+enter_autoindent_twice [set_autoindent(true)]:
+    "  ab", "A<CR><CR>x" => "  ab\n  \n  x",
+
+open_count: "ab\ncd", "3ox<Esc>" => ("ab\nx\nx\nx\ncd", 7),
+open_above_count: "ab", "2Ox<Esc>" => ("x\nx\nab", 2),
+open_count_autoindent [set_autoindent(true)]:
+    "  ab", "3ox<Esc>" => "  ab\n  x\n  x\n  x",
+open_count_enter: "ab", "2ox<CR>y<Esc>" => "ab\nx\ny\nx\ny",
+open_count_empty: "ab", "2o<Esc>" => "ab\n\n",
+
+open_dot: "ab", "ox<Esc>." => "ab\nx\nx",
+open_count_dot: "ab", "2ox<Esc>." => "ab\nx\nx\nx\nx",
+open_above_dot: "ab", "Ox<Esc>." => "x\nx\nab",
+open_undo: "ab", "ox<Esc>u" => "ab",
+open_count_inserted_register: "ab", "3oxy<Esc>" => { register '.': "xy" },
+
+);
@@ -34,6 +34,10 @@ x_empty: "x" => "",
 X: "iabcd<Esc>X<Left>X" => "bd",
 s: "iabcd<Esc>se<Esc>hsf" => "abfe",
 S: "iabcdef<Esc>hhhSghij" => "ghij",
+S_first_line: "iab<CR>cd<Esc>0Sx<Esc>" => "x\ncd",
+S_last_line: "iab<CR>cd<Esc>Sx<Esc>" => "ab\nx",
+S_count: "iab<CR>cd<CR>ef<Esc>02Sx<Esc>" => "x\nef",
+S_like_cc: "iab<CR>cd<Esc>0ccx<Esc>" => "x\ncd",
 r: "iabcd<Esc>Fbre" => "aecd",
 r_empty: "rx" => "",
 r_end: "iabc<Esc>rx" => "abx",
@@ -259,7 +263,7 @@ g_tilde_fail: "iaBc!<Esc>g~Fzaz" => "aBc!z",
 
 dp: "iabc<Esc>0dfb$p" => "cab",
 dd_: "iabc<Esc>dd" => "",
-ddp: "iabc<Esc>ddp" => "abc",
+ddp: "iabc<Esc>ddp" => "\nabc",
 dP: "iabc<Esc>0dfb$P" => "abc",
 
 y: "iabcdef<Esc>0yfcP" => "abcabcdef",
@@ -362,4 +366,20 @@ di2w_3words: "iabc def ghi<Esc>Fddi2wi." => "abc def .ghi",
 d2i2w: "iabc def ghi<Esc>Fdd2i2wi." => "abc def .ghi",
 _2di2w_possible: "iabc def ghi klm<Esc>Fd2d2iwi." => "abc .klm",
 
+di_quote: r#"ia "bc" d<Esc>0fbdi""# => r#"a "" d"#,
+da_quote: r#"ia "bc" d<Esc>0fbda""# => "a  d",
+di_quote_after_cursor: r#"ia "bc" d<Esc>0di""# => r#"a "" d"#,
+
+dip: "a\nb\n\nc\nd", "dip" => { content: "\nc\nd", register '"': "a\nb" },
+yip_several_blank_lines: "a\nb\n\n\nc", "fbyip" => { register '"': "a\nb" },
+dip_blank_lines: "a\n\n\nc", "lldip" => "a\nc",
+dip_count: "a\nb\n\nc", "d2ip" => "c",
+cip: "a\nb\n\nc", "cipx" => "x\n\nc",
+dip_count_too_big: "a\nb", "d3ip" => "a\nb",
+
+dap: "a\nb\n\nc\nd", "dap" => "c\nd",
+dap_last_paragraph: "a\n\nc\nd", "fcdap" => "a",
+dap_count: "a\n\nb\n\nc", "d2ap" => "c",
+dap_dot: "a\n\nc", "dap." => "",
+
 );
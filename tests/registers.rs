@@ -1,4 +1,11 @@
-use vim_buffer::Buffer;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use vim_buffer::{
+    ClipboardOption, ClipboardProvider, ClipboardSelection, MemoryProvider, Mode, RegisterType, SharedRegisters
+};
+
+mod common;
 
 #[test]
 fn start_empty() {
@@ -8,3 +15,692 @@ fn start_empty() {
     }
     assert_eq!(buffer.as_content(), "");
 }
+
+buffer_tests!(
+
+numbered_dd: r#"iabc<Esc>dd"1p"# => "\nabc",
+numbered_shift: r#"iabc<Esc>ddidef<Esc>dd"2p"# => "\nabc",
+numbered_small_delete: r#"iabc def<Esc>0dw"1p"# => "def",
+numbered_small_delete_minus: r#"iabc def<Esc>0dw"-P"# => "abc def",
+numbered_cc: r#"iabc<Esc>ccdef<Esc>"1p"# => "def\nabc",
+numbered_yank: r#"iabc<Esc>yy"1p"# => "abc",
+numbered_named: r#"iabc<Esc>"add"1P"# => "abc\n",
+numbered_lost: r#"i1<Esc>ddi2<Esc>ddi3<Esc>ddi4<Esc>ddi5<Esc>ddi6<Esc>ddi7<Esc>ddi8<Esc>ddi9<Esc>ddi10<Esc>dd"9P"# => "2\n",
+numbered_dot: r#"iaaa<Esc>ddibbb<Esc>ddiccc<Esc>dd"1Pu.u."# => "aaa\n",
+numbered_dot_twice: r#"iaaa<Esc>ddibbb<Esc>ddiccc<Esc>dd"1p.."# => "\nccc\nbbb\naaa",
+numbered_dot_named: r#"iaaa<Esc>"add"ap."# => "\naaa\naaa",
+
+yank_named: "abc def", r#""ayw"0p"# => "abc def",
+yank_named_keeps_copy: "abc def", r#"yw"byw"0p"# => "aabc bc def",
+delete_named: "abc def", r#""adw"-p"# => "def",
+delete_named_keeps_minus: "abc def", r#"x"bdw"-p"# => "daef",
+yank_clipboard_alias [set_clipboard_option(ClipboardOption::UnnamedPlus)]:
+    "abc def", r#""+yw"0P"# => "abc abc def",
+
+last_inserted_backspace_typed: "ab", "Axy<BS>z<Esc>" =>
+    { content: "abxz", register '.': "xz" },
+last_inserted_backspace_before_start: "abc", "A<BS><BS>x<Esc>" =>
+    { content: "ax", register '.': "x" },
+last_inserted_backspace_after_left: "xyz", "Aab<Left><Left><BS>c<Esc>" =>
+    { content: "xycab", register '.': "abc" },
+last_inserted_backspace_after_dedent [set_shiftwidth(2), set_expandtab(true)]:
+    "    ab", "Axy<C-d><BS><Esc>" => { content: "  abx", register '.': "x" },
+
+);
+
+#[test]
+fn register_types() {
+    let mut buffer = Buffer::from("abc def");
+    buffer.update_from_string(r#""ayw"byy"cdd"#).unwrap();
+    assert_eq!(
+        buffer.as_register('a'),
+        Some(("abc ".to_owned(), RegisterType::Charwise))
+    );
+    assert_eq!(
+        buffer.as_register('b'),
+        Some(("abc def".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(
+        buffer.as_register('c'),
+        Some(("abc def".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(
+        buffer.as_register('1'),
+        Some(("abc def".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(
+        buffer.as_register('"'),
+        Some(("abc def".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(buffer.as_register('d'), None);
+}
+
+#[test]
+fn register_type_append_keeps_type() {
+    let mut buffer = Buffer::from("abc def");
+    buffer.update_from_string(r#""ayw"Ayy"#).unwrap();
+    assert_eq!(
+        buffer.as_register('a'),
+        Some(("abc abc def".to_owned(), RegisterType::Charwise))
+    );
+}
+
+#[test]
+fn set_register() {
+    let mut buffer = Buffer::from("abc");
+    assert!(buffer.set_register('a', "x", RegisterType::Linewise));
+    assert!(buffer.set_register('A', "y", RegisterType::Charwise));
+    assert!(buffer.set_register('_', "z", RegisterType::Charwise));
+    assert_eq!(
+        buffer.as_register('a'),
+        Some(("xy".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(buffer.as_register('"'), None);
+    for reg in ['%', '#', ':', '/', '!'] {
+        assert!(!buffer.set_register(reg, "x", RegisterType::Charwise));
+    }
+    buffer.update_from_string(r#""ap"#).unwrap();
+    assert_eq!(buffer.as_content(), "abc\nxy");
+}
+
+#[test]
+fn list_registers() {
+    let mut buffer = Buffer::from("abc def");
+    assert_eq!(buffer.list_registers(), vec![]);
+    buffer.set_file_name("file.txt");
+    buffer.update_from_string(r#""qyiwwdwiabc<Esc>"#).unwrap();
+    assert_eq!(buffer.list_registers(), vec![
+        ('"', "def".to_owned(), RegisterType::Charwise),
+        ('q', "abc".to_owned(), RegisterType::Charwise),
+        ('-', "def".to_owned(), RegisterType::Charwise),
+        ('.', "abc".to_owned(), RegisterType::Charwise),
+        ('%', "file.txt".to_owned(), RegisterType::Charwise),
+    ]);
+}
+
+#[test]
+fn clear_register() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string(r#""ayy"byy"#).unwrap();
+    assert!(buffer.clear_register('A'));
+    assert!(buffer.clear_register('_'));
+    assert_eq!(buffer.as_register('a'), None);
+    assert_eq!(
+        buffer.as_register('b'),
+        Some(("abc".to_owned(), RegisterType::Linewise))
+    );
+    buffer.set_file_name("file.txt");
+    for reg in ['%', '#', ':', '/', '.', '!'] {
+        assert!(!buffer.clear_register(reg));
+    }
+    assert_eq!(
+        buffer.as_register('%'),
+        Some(("file.txt".to_owned(), RegisterType::Charwise))
+    );
+    buffer.update_from_string(r#""ap"#).unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+}
+
+#[test]
+fn clear_clipboard_register() {
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc");
+    buffer.set_clipboard_provider(provider.clone());
+    buffer.update_from_string(r#""+yy"#).unwrap();
+    assert!(buffer.clear_register('+'));
+    assert_eq!(provider.last(ClipboardSelection::Clipboard).unwrap(), "");
+    assert!(buffer.list_registers().iter().all(|(reg, ..)| *reg != '+'));
+}
+
+fn paste(
+    content: &str,
+    keys: &str,
+    reg: &str,
+    ty: RegisterType,
+) -> (String, usize) {
+    let mut buffer = Buffer::from(content);
+    assert!(buffer.set_register('a', reg, ty));
+    buffer.update_from_string(keys).unwrap();
+    (buffer.as_content().to_owned(), buffer.as_cursor())
+}
+
+#[test]
+fn paste_linewise() {
+    let ty = RegisterType::Linewise;
+    assert_eq!(
+        paste("abc\ndef", r#""ap"#, "x", ty),
+        ("abc\nx\ndef".to_owned(), 4)
+    );
+    assert_eq!(
+        paste("abc\ndef", r#""aP"#, "x", ty),
+        ("x\nabc\ndef".to_owned(), 0)
+    );
+    assert_eq!(
+        paste("abc", r#"$"aP"#, "x\ny", ty),
+        ("x\ny\nabc".to_owned(), 0)
+    );
+    assert_eq!(
+        paste("abc", r#"$"ap"#, "x\ny", ty),
+        ("abc\nx\ny".to_owned(), 4)
+    );
+}
+
+#[test]
+fn paste_blockwise() {
+    let ty = RegisterType::Blockwise;
+    assert_eq!(
+        paste("abc\ndef", r#"l"ap"#, "12\n34", ty),
+        ("ab12c\nde34f".to_owned(), 2)
+    );
+    assert_eq!(
+        paste("abc\ndef", r#"l"aP"#, "12\n34", ty),
+        ("a12bc\nd34ef".to_owned(), 1)
+    );
+    assert_eq!(
+        paste("abc\nd", r#"ll"ap"#, "1\n2\n3", ty),
+        ("abc1\nd  2\n   3".to_owned(), 3)
+    );
+}
+
+#[test]
+fn paste_charwise() {
+    let ty = RegisterType::Charwise;
+    assert_eq!(
+        paste("abc\ndef", r#""ap"#, "x\ny", ty),
+        ("ax\nybc\ndef".to_owned(), 1)
+    );
+    assert_eq!(paste("abc", r#""ap"#, "xy", ty), ("axybc".to_owned(), 2));
+    assert_eq!(paste("abc", r#""aP"#, "xy", ty), ("xyabc".to_owned(), 1));
+    assert_eq!(paste("abc", r#"$"aP"#, "xy", ty), ("abxyc".to_owned(), 3));
+    assert_eq!(paste("é", r#""ap"#, "ü", ty), ("éü".to_owned(), 2));
+}
+
+#[test]
+fn paste_count() {
+    let ty = RegisterType::Charwise;
+    assert_eq!(paste("abc", r#""a3p"#, "x", ty), ("axxxbc".to_owned(), 3));
+    assert_eq!(paste("abc", r#"$2"aP"#, "xy", ty), ("abxyxyc".to_owned(), 5));
+    assert_eq!(
+        paste("abc\ndef", r#""a2p"#, "x", RegisterType::Linewise),
+        ("abc\nx\nx\ndef".to_owned(), 4)
+    );
+    assert_eq!(
+        paste("abc\ndef", r#"l"a2p"#, "12\n3", RegisterType::Blockwise),
+        ("ab1212c\nde3 3f".to_owned(), 2)
+    );
+}
+
+#[test]
+fn paste_count_single_edit() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string("yl3pu").unwrap();
+    assert_eq!(buffer.as_content(), "abc");
+}
+
+#[test]
+fn paste_huge_count() {
+    let mut buffer = Buffer::from("abc\ndef");
+    for keys in [
+        "yl99999999999999999999p",
+        "yy99999999999999999999p",
+        "<C-v>jy99999999999999999999p",
+    ] {
+        buffer.update_from_string(keys).unwrap();
+        assert_eq!(buffer.as_content(), "abc\ndef", "Keys: {keys}");
+    }
+}
+
+#[test]
+fn paste_skip() {
+    let ty = RegisterType::Charwise;
+    assert_eq!(paste("abc", r#""agp"#, "xy", ty), ("axybc".to_owned(), 3));
+    assert_eq!(paste("abc", r#""agP"#, "xy", ty), ("xyabc".to_owned(), 2));
+    assert_eq!(paste("abc", r#""a2gp"#, "x", ty), ("axxbc".to_owned(), 3));
+    let ty = RegisterType::Linewise;
+    assert_eq!(
+        paste("abc\ndef", r#""agp"#, "x", ty),
+        ("abc\nx\ndef".to_owned(), 6)
+    );
+    assert_eq!(
+        paste("abc\ndef", r#""agP"#, "x", ty),
+        ("x\nabc\ndef".to_owned(), 2)
+    );
+    assert_eq!(paste("abc", r#""agp"#, "x", ty), ("abc\nx".to_owned(), 4));
+    assert_eq!(
+        paste("abc\ndef", r#"l"agp"#, "12\n3", RegisterType::Blockwise),
+        ("ab12c\nde3f".to_owned(), 9)
+    );
+}
+
+#[test]
+fn paste_indent() {
+    let ty = RegisterType::Linewise;
+    assert_eq!(
+        paste("    abc", r#""a]p"#, "x\n  y\n\nz", ty),
+        ("    abc\n    x\n      y\n\n    z".to_owned(), 8)
+    );
+    assert_eq!(
+        paste("  abc", r#""a[p"#, "\tx\n\t\ty", ty),
+        ("  x\n  \ty\n  abc".to_owned(), 0)
+    );
+    assert_eq!(
+        paste("abc", r#""a]P"#, "    x\ny", ty),
+        ("x\ny\nabc".to_owned(), 0)
+    );
+    assert_eq!(
+        paste("  abc", r#""a2]p"#, "x", ty),
+        ("  abc\n  x\n  x".to_owned(), 6)
+    );
+    assert_eq!(
+        paste("  abc", r#""a]p"#, "x", RegisterType::Charwise),
+        (" x abc".to_owned(), 1)
+    );
+}
+
+#[test]
+fn paste_dot_repeat() {
+    let ty = RegisterType::Charwise;
+    assert_eq!(paste("abc", r#""a2p."#, "x", ty), ("axxxxbc".to_owned(), 4));
+    assert_eq!(paste("abc", r#""agp."#, "x", ty), ("axbxc".to_owned(), 4));
+    assert_eq!(
+        paste("  abc", r#""a]p."#, "x", RegisterType::Linewise),
+        ("  abc\n  x\n  x".to_owned(), 10)
+    );
+}
+
+#[test]
+fn dd_multiline() {
+    let mut buffer = Buffer::from("abc\ndef\nghi");
+    buffer.update_from_string("dd").unwrap();
+    assert_eq!(buffer.as_content(), "def\nghi");
+    buffer.update_from_string("2dd").unwrap();
+    assert_eq!(buffer.as_content(), "");
+    assert_eq!(
+        buffer.as_register('1'),
+        Some(("def\nghi".to_owned(), RegisterType::Linewise))
+    );
+    assert_eq!(
+        buffer.as_register('2'),
+        Some(("abc".to_owned(), RegisterType::Linewise))
+    );
+}
+
+#[test]
+fn cc_multiline() {
+    let mut buffer = Buffer::from("abc\ndef");
+    buffer.update_from_string("ccx<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "x\ndef");
+    assert_eq!(
+        buffer.as_register('"'),
+        Some(("abc".to_owned(), RegisterType::Linewise))
+    );
+}
+
+type Log = Arc<Mutex<Vec<(ClipboardSelection, String)>>>;
+
+#[derive(Debug, Default, Clone)]
+struct SharedProvider(Log);
+
+impl SharedProvider {
+    fn last(&self, selection: ClipboardSelection) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(sel, _)| *sel == selection)
+            .map(|(_, content)| content.clone())
+    }
+
+    fn push(&self, selection: ClipboardSelection, content: &str) {
+        self.0.lock().unwrap().push((selection, content.to_owned()));
+    }
+}
+
+impl ClipboardProvider for SharedProvider {
+    fn get(&mut self, selection: ClipboardSelection) -> Option<String> {
+        self.last(selection)
+    }
+
+    fn set(&mut self, selection: ClipboardSelection, content: &str) -> bool {
+        self.push(selection, content);
+        true
+    }
+}
+
+#[test]
+fn clipboard_without_provider() {
+    let mut buffer = Buffer::from("abc def");
+    buffer.update_from_string(r#""+yw"*yiw$"+p$"*p"#).unwrap();
+    assert_eq!(buffer.as_content(), "abc defabc abc");
+}
+
+#[test]
+fn clipboard_provider_write() {
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_clipboard_provider(provider.clone());
+    buffer.update_from_string(r#""+yw"*yy"#).unwrap();
+    assert_eq!(provider.last(ClipboardSelection::Clipboard).unwrap(), "abc ");
+    assert_eq!(
+        provider.last(ClipboardSelection::Primary).unwrap(),
+        "abc def\n"
+    );
+    buffer.update_from_string("yw").unwrap();
+    assert_eq!(provider.0.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn clipboard_provider_read() {
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc");
+    buffer.set_clipboard_provider(provider.clone());
+    provider.push(ClipboardSelection::Clipboard, "xyz");
+    buffer.update_from_string(r#""+p"#).unwrap();
+    assert_eq!(buffer.as_content(), "axyzbc");
+    provider.push(ClipboardSelection::Primary, "line\n");
+    buffer.update_from_string(r#""*P"#).unwrap();
+    assert_eq!(buffer.as_content(), "line\naxyzbc");
+    assert_eq!(
+        buffer.as_register('*'),
+        Some(("line".to_owned(), RegisterType::Linewise))
+    );
+}
+
+#[test]
+fn clipboard_keeps_blockwise() {
+    let mut buffer = Buffer::from("abc");
+    buffer.set_clipboard_provider(MemoryProvider::default());
+    assert!(buffer.set_register('+', "a\nb", RegisterType::Blockwise));
+    assert_eq!(
+        buffer.as_register('+'),
+        Some(("a\nb".to_owned(), RegisterType::Blockwise))
+    );
+}
+
+#[test]
+fn clipboard_unnamedplus() {
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_clipboard_provider(provider.clone());
+    buffer.set_clipboard_option(ClipboardOption::UnnamedPlus);
+    buffer.update_from_string("yw").unwrap();
+    assert_eq!(provider.last(ClipboardSelection::Clipboard).unwrap(), "abc ");
+    assert_eq!(provider.last(ClipboardSelection::Primary), None);
+    provider.push(ClipboardSelection::Clipboard, "xyz");
+    buffer.update_from_string("$p").unwrap();
+    assert_eq!(buffer.as_content(), "abc defxyz");
+}
+
+#[test]
+fn clipboard_unnamed() {
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_clipboard_provider(provider.clone());
+    buffer.set_clipboard_option(ClipboardOption::Unnamed);
+    buffer.update_from_string("dw").unwrap();
+    assert_eq!(provider.last(ClipboardSelection::Primary).unwrap(), "abc ");
+    buffer.update_from_string(r#""ayw"#).unwrap();
+    assert_eq!(provider.0.lock().unwrap().len(), 1);
+}
+
+#[derive(Debug, Default, Clone)]
+struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+impl SharedWriter {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn osc52_write() {
+    let writer = SharedWriter::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_osc52_writer(writer.clone(), 100);
+    buffer.update_from_string(r#""+yw"#).unwrap();
+    assert_eq!(writer.take(), b"\x1b]52;c;YWJjIA==\x07");
+    buffer.update_from_string(r#""*yy"#).unwrap();
+    assert_eq!(writer.take(), b"\x1b]52;p;YWJjIGRlZgo=\x07");
+    buffer.update_from_string(r#"yw"ayw"#).unwrap();
+    assert_eq!(writer.take(), b"");
+}
+
+#[test]
+fn osc52_unnamedplus() {
+    let writer = SharedWriter::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_osc52_writer(writer.clone(), 100);
+    buffer.set_clipboard_option(ClipboardOption::UnnamedPlus);
+    buffer.update_from_string("dw").unwrap();
+    assert_eq!(writer.take(), b"\x1b]52;c;YWJjIA==\x07");
+    assert_eq!(
+        buffer.as_register('+'),
+        Some(("abc ".to_owned(), RegisterType::Charwise))
+    );
+}
+
+#[test]
+fn osc52_max_len() {
+    let writer = SharedWriter::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_osc52_writer(writer.clone(), 8);
+    assert!(buffer.set_register('+', "abc ", RegisterType::Charwise));
+    assert_eq!(writer.take(), b"\x1b]52;c;YWJjIA==\x07");
+    assert!(buffer.set_register('+', "abc def", RegisterType::Charwise));
+    assert_eq!(writer.take(), b"");
+    assert_eq!(
+        buffer.as_register('+'),
+        Some(("abc def".to_owned(), RegisterType::Charwise))
+    );
+}
+
+#[test]
+fn osc52_max_len_yank() {
+    let writer = SharedWriter::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_osc52_writer(writer.clone(), 1);
+    buffer.update_from_string(r#"x"+yw."#).unwrap();
+    assert_eq!(writer.take(), b"");
+    assert_eq!(buffer.as_content(), "bc def");
+    assert_eq!(
+        buffer.as_register('+'),
+        Some(("bc ".to_owned(), RegisterType::Charwise))
+    );
+}
+
+#[test]
+fn osc52_with_provider() {
+    let writer = SharedWriter::default();
+    let provider = SharedProvider::default();
+    let mut buffer = Buffer::from("abc def");
+    buffer.set_osc52_writer(writer.clone(), 100);
+    buffer.set_clipboard_provider(provider.clone());
+    buffer.update_from_string(r#""+yw"#).unwrap();
+    assert_eq!(writer.take(), b"\x1b]52;c;YWJjIA==\x07");
+    assert_eq!(provider.last(ClipboardSelection::Clipboard).unwrap(), "abc ");
+}
+
+#[test]
+fn expression_paste() {
+    let mut buffer = Buffer::from("total: ");
+    buffer.update_from_string(r#"$"=6 * 7<CR>p"#).unwrap();
+    assert_eq!(buffer.as_content(), "total: 42");
+    assert_eq!(
+        buffer.as_register('='),
+        Some(("42".to_owned(), RegisterType::Charwise))
+    );
+    buffer.update_from_string("$.").unwrap();
+    assert_eq!(buffer.as_content(), "total: 4242");
+}
+
+#[test]
+fn expression_count() {
+    let mut buffer = Buffer::default();
+    buffer.update_from_string(r#"3"='ab' . 1.5<CR>P"#).unwrap();
+    assert_eq!(buffer.as_content(), "ab1.5ab1.5ab1.5");
+}
+
+#[test]
+fn expression_empty_reuses_result() {
+    let mut buffer = Buffer::default();
+    buffer.update_from_string(r#""=toupper('x')<CR>p"=<CR>p"#).unwrap();
+    assert_eq!(buffer.as_content(), "XX");
+}
+
+#[test]
+fn expression_insert() {
+    let mut buffer = Buffer::default();
+    buffer.update_from_string("ia<C-r>=repeat('-', 3)<CR>b<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "a---b");
+    buffer.update_from_string("$.").unwrap();
+    assert_eq!(buffer.as_content(), "a---ba---b");
+}
+
+#[test]
+fn expression_cancel() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string(r#""=1+<BS><BS>"#).unwrap();
+    assert_eq!(buffer.as_expression(), Some(""));
+    buffer.update_from_string("<Esc>p").unwrap();
+    assert_eq!(buffer.as_expression(), None);
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+    assert_eq!(buffer.as_content(), "abc");
+    buffer.update_from_string(r#""=2<BS><BS>x"#).unwrap();
+    assert_eq!(buffer.as_content(), "bc");
+}
+
+#[test]
+fn expression_invalid() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string(r#""=1 +<CR>"#).unwrap();
+    assert_eq!(buffer.as_mode(), Mode::Normal);
+    assert_eq!(buffer.as_register('='), None);
+    buffer.update_from_string("ix<C-r>=1/0<CR>y").unwrap();
+    assert_eq!(buffer.as_mode(), Mode::Insert);
+    assert_eq!(buffer.as_content(), "xyabc");
+}
+
+#[test]
+fn insert_register() {
+    let mut buffer = Buffer::from("abc");
+    buffer.update_from_string(r#""ayiwA <C-r>a!<Esc>"#).unwrap();
+    assert_eq!(buffer.as_content(), "abc abc!");
+    buffer.update_from_string("yyA<C-r>\"").unwrap();
+    assert_eq!(buffer.as_content(), "abc abc!abc abc!\n");
+}
+
+#[test]
+fn last_inserted() {
+    let mut buffer = Buffer::default();
+    buffer.update_from_string("iabx<BS>c<Esc>").unwrap();
+    assert_eq!(
+        buffer.as_register('.'),
+        Some(("abc".to_owned(), RegisterType::Charwise))
+    );
+    buffer.update_from_string("A-<C-a>-<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "abc-abc-");
+    assert_eq!(buffer.as_register('.').unwrap().0, "-abc-");
+    buffer.update_from_string("0\".P").unwrap();
+    assert_eq!(buffer.as_content(), "-abc-abc-abc-");
+}
+
+#[test]
+fn last_inserted_change() {
+    let mut buffer = Buffer::from("abc def");
+    buffer.update_from_string("ciwxyz<Esc>wi<C-r>.<Esc>").unwrap();
+    assert_eq!(buffer.as_content(), "xyz xyzdef");
+    assert_eq!(buffer.as_register('.').unwrap().0, "xyz");
+}
+
+#[test]
+fn read_only_registers() {
+    let mut buffer = Buffer::from("abc");
+    for reg in ['.', '%', '#', ':', '/'] {
+        assert!(!buffer.set_register(reg, "x", RegisterType::Charwise));
+        assert_eq!(buffer.as_register(reg), None);
+    }
+    buffer.set_file_name("form");
+    buffer.set_alternate_file_name("field");
+    buffer.set_last_command("w");
+    buffer.update_from_string(r#"$"%p"#).unwrap();
+    assert_eq!(buffer.as_content(), "abcform");
+    buffer.update_from_string(r##"$"#p$":p"##).unwrap();
+    assert_eq!(buffer.as_content(), "abcformfieldw");
+    buffer.update_from_string(r#""%yy"#).unwrap();
+    assert_eq!(buffer.as_register('%').unwrap().0, "form");
+}
+
+#[test]
+fn shared_registers_same_thread() {
+    let registers = SharedRegisters::default();
+    let mut first = Buffer::from("abc def");
+    let mut second = Buffer::from("xyz");
+    first.use_shared_registers(&registers);
+    second.use_shared_registers(&registers);
+    first.update_from_string(r#""ayiwwdw"#).unwrap();
+    second.update_from_string(r#"$"ap$p"#).unwrap();
+    assert_eq!(second.as_content(), "xyzabcdef");
+    assert_eq!(first.as_content(), "abc ");
+    second.update_from_string("u").unwrap();
+    assert_eq!(second.as_content(), "xyzabc");
+    assert_eq!(first.as_content(), "abc ");
+    assert_eq!(first.as_mode(), Mode::Normal);
+}
+
+#[test]
+fn shared_registers_clipboard() {
+    let registers = SharedRegisters::default();
+    let provider = SharedProvider::default();
+    let mut first = Buffer::from("abc");
+    let mut second = Buffer::default();
+    first.use_shared_registers(&registers);
+    second.use_shared_registers(&registers);
+    first.set_clipboard_provider(provider.clone());
+    second.update_from_string(r#"i123<Esc>"+yy"#).unwrap();
+    assert_eq!(provider.last(ClipboardSelection::Clipboard).unwrap(), "123\n");
+}
+
+#[test]
+fn shared_registers() {
+    let registers = SharedRegisters::default();
+    let handles = (0..4)
+        .map(|idx| {
+            let registers = registers.clone();
+            std::thread::spawn(move || {
+                let mut buffer = Buffer::from(idx.to_string());
+                buffer.use_shared_registers(&registers);
+                buffer.update_from_string(r#""Ayy"#).unwrap();
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let mut buffer = Buffer::default();
+    buffer.use_shared_registers(&registers);
+    let mut content = buffer.as_register('a').unwrap().0.into_bytes();
+    content.sort_unstable();
+    assert_eq!(content, b"0123");
+}
+
+#[test]
+fn own_registers() {
+    let registers = SharedRegisters::default();
+    let mut shared = Buffer::from("abc");
+    shared.use_shared_registers(&registers);
+    let mut own = Buffer::default();
+    shared.update_from_string("yiw").unwrap();
+    own.update_from_string("p").unwrap();
+    assert!(own.is_empty());
+}
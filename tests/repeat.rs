@@ -0,0 +1,63 @@
+mod common;
+
+buffer_tests!(
+
+delete_char: "abcdef", "x." => ("cdef", 0),
+delete_char_count: "abcdef", "x3." => ("ef", 0),
+delete_char_typed_count: "abcdef", "2x." => ("ef", 0),
+delete_char_typed_count_override: "abcdef", "2x3." => ("f", 0),
+delete_char_count_kept: "abcdef", "x2.." => ("f", 0),
+
+delete_char_before: "abcdef", "5lX." => ("abcf", 3),
+delete_char_before_count: "abcdef", "5lX3." => "af",
+delete_char_before_typed_count: "abcdefg", "6l2X." => "abg",
+
+delete_word: "a b c d e", "dw." => "c d e",
+delete_word_count: "a b c d e", "dw2." => "d e",
+delete_word_motion_count: "a b c d e", "d2w." => "e",
+delete_word_typed_count_override: "a b c d e f", "2dw3." => "f",
+delete_word_both_counts_override: "a b c d e f", "2d2w1." => "f",
+
+delete_line: "1\n2\n3\n4\n5", "dd." => "3\n4\n5",
+delete_line_count: "1\n2\n3\n4\n5", "dd2." => "4\n5",
+delete_line_typed_count: "1\n2\n3\n4\n5", "2dd." => "5",
+delete_line_typed_count_override: "1\n2\n3\n4\n5", "3dd1." => "5",
+delete_line_count_kept: "1\n2\n3\n4\n5\n6", "dd2.." => "6",
+
+change_word: "ab cd", "ciwx<Esc>w." => "x x",
+change_word_count: "ab cd ef gh", "ciwx<Esc>w3." => "x x gh",
+substitute: "abcdef", "sx<Esc>l." => ("xxcdef", 1),
+substitute_count: "abcdef", "sx<Esc>l3." => "xxef",
+substitute_typed_count: "abcdef", "2sx<Esc>l." => "xxef",
+substitute_typed_count_override: "abcdef", "2sx<Esc>l3." => "xxf",
+
+append_end: "ab", "A!<Esc>0." => "ab!!",
+append_end_count: "ab", "A!<Esc>02." => "ab!!!",
+insert_start: "ab", "I-<Esc>$." => "--ab",
+append: "ab", "a-<Esc>." => "a--b",
+open_line: "a\nb", "ox<Esc>." => "a\nx\nx\nb",
+
+paste: "ab", "ylp." => ("aaab", 2),
+paste_count: "ab", "ylp3." => "aaaaab",
+paste_typed_count: "ab", "yl3p." => "aaaaaaab",
+
+toggle_case: "abcdef", "~." => ("ABcdef", 2),
+toggle_case_typed_count: "abcdef", "3~." => ("ABCDEF", 5),
+toggle_case_typed_count_override: "abcdef", "3~2." => "ABCDEf",
+toggle_case_count: "abcdef", "0~3." => ("ABCDef", 4),
+upper_case: "ab cd", "gUiww." => "AB CD",
+replace: "abc", "rxl." => "xxc",
+
+join: "a\nb\nc\nd\ne", "J." => "a b c\nd\ne",
+join_count: "a\nb\nc\nd\ne", "J3." => "a b c d\ne",
+
+yank_register: "ab cd", "\"ayiww." => { register 'a': "cd" },
+yank_append_register: "ab cd", "\"Ayiww." => { register 'a': "abcd" },
+delete_register: "1\n2\n3", "\"add." => { content: "3", register 'a': "2" },
+black_hole_register: "1\n2\n3", "yy\"_dd." => { register '"': "1" },
+paste_register_count: "ab", "\"ayl\"ap2." => "aaaab",
+
+count_dot_undo: "abcdef", "x3.u" => "bcdef",
+count_dot_undo_lines: "1\n2\n3\n4", "dd2.u" => "2\n3\n4",
+
+);
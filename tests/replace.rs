@@ -14,5 +14,26 @@ bs: "iabcdef<Esc>0fdRghi<BS><BS><BS><BS><BS><BS>jk" => "jkcdef",
 bs_start: "iabc<Esc>0Rdef<BS><BS><BS><BS>." => ".bc",
 bs_too_far: "iabc<Esc>0Rdefghi<BS><BS>k" => "defgk",
 arrows_bs: "iabc<Esc>0Rx<Right>y<BS><BS><BS><BS>" => "xbc",
+count: "iabcdef<Esc>02Rxy<Esc>" => "xyxyef",
+count_past_end: "iab<Esc>03Rxy<Esc>" => "xyxyxy",
+count_bs: "iabcdef<Esc>02Rxyz<BS><Esc>" => "xyxyef",
+count_dot: "iabcdefgh<Esc>02Rx<Esc>l." => "xxxxefgh",
+count_dot_override: "iabcdefgh<Esc>0Rx<Esc>l3." => "xxxxefgh",
+
+gr_word: "iabc def<Esc>0yiwwgriw" => "abc abc",
+gr_keeps_unnamed: "iabc def ghi<Esc>0yiwwgriwwgriw" => "abc abc abc",
+gr_register: r#"iabc def<Esc>0"ayiwwyiw"agriw"# => "abc abc",
+grr: "iabc<Esc>yyA def<Esc>grr" => "abc",
+gr_dot: "iabc def ghi<Esc>0yiwwgriww." => "abc abc abc",
+gr_empty_register: "iabc<Esc>0griw" => "abc",
+
+cx_words: "iabc def<Esc>0cxiwwcxiw" => "def abc",
+cx_backwards: "iabc def<Esc>$cxiw0cxiw" => "def abc",
+cx_different_lengths: "iab cdef gh<Esc>0cxiw$cxiw" => "gh cdef ab",
+cx_nested: "i(abc) d<Esc>0cxa(lcxiw" => "abc d",
+cx_dot: "iabc def<Esc>0cxiww." => "def abc",
+cxc: "iabc def<Esc>0cxiwcxcwcxiw" => "abc def",
+cxx: "iabc def<Esc>0cxxcxiw" => "abc",
+cx_forgotten_on_edit: "iabc def<Esc>0cxiwxwcxiw" => "bc def",
 
 );
@@ -0,0 +1,34 @@
+mod common;
+
+buffer_tests!(
+
+ysiw_closing: "iabc def<Esc>0ysiw)" => "(abc) def",
+ysiw_opening: "iabc def<Esc>0ysiw(" => "( abc ) def",
+ysiw_alias: "iabc def<Esc>0ysiwb" => "(abc) def",
+ysiw_quote: r#"iabc def<Esc>0ysiw""# => r#""abc" def"#,
+ys2w: "iabc def ghi<Esc>0ys2e]" => "[abc def] ghi",
+yss: "i  abc def<Esc>yss}" => "  {abc def}",
+ysiw_tag: "iabc def<Esc>0ysiw<lt>em<CR>" => "<em>abc</em> def",
+ysiw_tag_closing: "iabc def<Esc>0ysiwtem<gt>" => "<em>abc</em> def",
+ysiw_tag_attributes: r#"iabc<Esc>0ysiw<lt>a href="x"<gt>"# => r#"<a href="x">abc</a>"#,
+ysiw_tag_cancel: "iabc<Esc>0ysiw<lt>em<Esc>x" => "bc",
+ysiw_dot: "iabc def<Esc>0ysiw)W." => "(abc) (def)",
+ysiw_tag_dot: "iabc def<Esc>0ysiw<lt>em<gt>W." => "<em>abc</em> <em>def</em>",
+ysiw_undo: "iabc def<Esc>0ysiw(u" => "abc def",
+
+cs_quotes: r#"i"abc" def<Esc>0lcs"'"# => "'abc' def",
+cs_brackets: "i(abc) def<Esc>0lcs([" => "[ abc ] def",
+cs_spaced: "i( abc ) def<Esc>0lcs(]" => "[abc] def",
+cs_tag: r#"i"abc"<Esc>0lcs"<lt>b<gt>"# => "<b>abc</b>",
+cs_missing: "iabc<Esc>0cs\"'" => "abc",
+cs_dot: r#"i"abc" "def"<Esc>0lcs"'fd."# => "'abc' 'def'",
+cs_undo: r#"i"abc"<Esc>0lcs"'u"# => r#""abc""#,
+
+ds_parens: "ia(bc)d<Esc>0fbds(" => "abcd",
+ds_spaced: "ia( bc )d<Esc>0fbds(" => "abcd",
+ds_closing_keeps_spaces: "ia( bc )d<Esc>0fbds)" => "a bc d",
+ds_quote: r#"ia"bc"d<Esc>0fbds""# => "abcd",
+ds_nested: "i((abc))<Esc>0fbds)" => "(abc)",
+ds_dot: "i(a) (b)<Esc>0lds)fb." => "a b",
+
+);